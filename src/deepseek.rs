@@ -1,142 +1,885 @@
-use completion::Chunk;
-use reqwest::{header, Client as HttpClient};
-use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
-use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
+use bytes::Bytes;
+use completion::{Chunk, ChunkAccumulator};
+use futures::{stream, Stream, StreamExt};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use reqwest::{Client as HttpClient, Proxy, RequestBuilder, Response};
+use retry::{ExponentialBackoff, Failure, RetryPolicy};
+use serde::{de::DeserializeOwned, Serialize};
 use serde_json::json;
-use std::time::Duration;
+use std::collections::BTreeMap;
+use std::env;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use think::{Split, ThinkSplitter};
+use tokio::time;
 
+use crate::{BuildError, Error, Result, StreamError};
+
+pub mod agent;
 pub mod completion;
+pub mod conversation;
+pub mod model;
+pub mod openrouter;
 pub mod request;
+pub mod retry;
+pub mod schema;
+pub mod sse;
+pub mod think;
+pub mod tools;
+
+pub use model::{Capabilities, Model};
 
-pub const MODEL: &str = "deepseek/deepseek-r1-distill-llama-70b";
+const CHAT_COMPLETIONS: &str = "chat/completions";
+const COMPLETIONS: &str = "completions";
 
 pub struct Config {
-    pub base_url: &'static str,
-    pub model: &'static str,
-    pub connection_timeout: Duration,
-    pub max_retries: u32,
+    /// Scheme and host of the service, e.g. `https://api.deepseek.com`.
+    pub base_url: String,
+    /// Path prefix of the API on `base_url`, e.g. `/api/v1` for OpenRouter.
+    pub api_path: String,
+    /// Whether the service is OpenRouter. Otherwise [`request::Chat::openrouter`]
+    /// is not sent.
+    pub openrouter: bool,
+    /// Path prefix of beta features such as FIM completions on `base_url`,
+    /// `None` if the provider doesn't offer them.
+    pub beta_path: Option<String>,
+    /// Model used by requests that don't name one.
+    pub model: Model,
+    /// Maximum time to establish a connection.
+    pub connect_timeout: Duration,
+    /// Maximum duration of a non-streaming completion, including the response body.
+    pub request_timeout: Duration,
+    /// Maximum silence on a streamed completion, both before the response arrives
+    /// and between two chunks. Keep-alive comments count as activity.
+    pub idle_timeout: Duration,
+    /// Decides which failed requests are sent again. Streams are only retried
+    /// until the response starts.
+    pub retry_policy: Arc<dyn RetryPolicy>,
+    /// Moves `<think>` blocks in the content of chat completions into
+    /// `reasoning_content`, see [`think`]. Off by default.
+    ///
+    /// Streamed content that doesn't open with `<think>` is held back until a
+    /// `</think>` or the end of the stream, so models that don't reason lose
+    /// incremental output.
+    pub split_think: bool,
 }
 
 impl Config {
+    pub const DEEPSEEK_URL: &'static str = "https://api.deepseek.com";
+    pub const OPENROUTER_URL: &'static str = "https://openrouter.ai";
+
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Targets the DeepSeek API directly.
+    pub fn deepseek() -> Self {
+        Self {
+            base_url: String::from(Self::DEEPSEEK_URL),
+            api_path: String::new(),
+            openrouter: false,
+            beta_path: Some(String::from("/beta")),
+            model: Model::Chat,
+            ..Self::default()
+        }
+    }
+
+    /// Targets DeepSeek models through OpenRouter.
+    pub fn openrouter() -> Self {
+        Self {
+            base_url: String::from(Self::OPENROUTER_URL),
+            api_path: String::from("/api/v1"),
+            ..Self::default()
+        }
+    }
+
+    /// Builds the full URL of an API endpoint such as `chat/completions`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rgi::deepseek::Config;
+    /// assert_eq!(
+    ///     Config::deepseek().endpoint("chat/completions"),
+    ///     "https://api.deepseek.com/chat/completions"
+    /// );
+    /// assert_eq!(
+    ///     Config::openrouter().endpoint("/chat/completions"),
+    ///     "https://openrouter.ai/api/v1/chat/completions"
+    /// );
+    /// ```
+    pub fn endpoint(&self, endpoint: &str) -> String {
+        join(&self.base_url, [self.api_path.as_str(), endpoint])
+    }
+
+    /// Builds the full URL of a beta endpoint such as `completions`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rgi::deepseek::Config;
+    /// assert_eq!(
+    ///     Config::deepseek().beta_endpoint("completions").as_deref(),
+    ///     Some("https://api.deepseek.com/beta/completions")
+    /// );
+    /// assert_eq!(Config::openrouter().beta_endpoint("completions"), None);
+    /// ```
+    pub fn beta_endpoint(&self, endpoint: &str) -> Option<String> {
+        let beta_path = self.beta_path.as_deref()?;
+        Some(join(&self.base_url, [beta_path, endpoint]))
+    }
+}
+
+fn join<'a>(base_url: &str, segments: impl IntoIterator<Item = &'a str>) -> String {
+    let mut url = String::from(base_url.trim_end_matches('/'));
+
+    for segment in segments {
+        let segment = segment.trim_matches('/');
+        if !segment.is_empty() {
+            url.push('/');
+            url.push_str(segment);
+        }
+    }
+
+    url
 }
 
 impl Default for Config {
+    /// Defaults to DeepSeek R1 Distill Llama 70B on OpenRouter.
     fn default() -> Self {
         Self {
-            base_url: "https://openrouter.ai",
-            model: MODEL,
-            max_retries: 3,
-            // Keep in mind that this should be way lower when streaming completion chunks.
-            connection_timeout: Duration::from_secs(3600),
+            base_url: String::from(Self::OPENROUTER_URL),
+            api_path: String::from("/api/v1"),
+            openrouter: true,
+            beta_path: None,
+            model: Model::OpenRouterR1DistillLlama70b,
+            retry_policy: Arc::new(ExponentialBackoff::default()),
+            connect_timeout: Duration::from_secs(10),
+            // Reasoning models may think for several minutes before answering.
+            request_timeout: Duration::from_secs(600),
+            idle_timeout: Duration::from_secs(30),
+            split_think: false,
         }
     }
 }
 
+/// Configures and creates a [`Client`].
+///
+/// # Examples
+/// ```
+/// # use rgi::{deepseek::{Client, Config}, BuildError, Error};
+/// let client = Client::builder()
+///     .api_key("sk-...")
+///     .config(Config::openrouter())
+///     .header("HTTP-Referer", "https://example.com")
+///     .header("X-Title", "Example")
+///     .build();
+/// assert!(client.is_ok());
+///
+/// let client = Client::builder().api_key("sk-...\n").build();
+/// assert!(matches!(client, Err(Error::Build(BuildError::InvalidApiKey))));
+///
+/// let client = Client::builder().api_key_env("RGI_DOCTEST_UNSET").build();
+/// assert!(matches!(client, Err(Error::Build(BuildError::MissingEnv(_)))));
+/// ```
+#[derive(Default)]
+pub struct ClientBuilder {
+    api_key: Option<ApiKey>,
+    config: Config,
+    headers: Vec<(String, String)>,
+    proxy: Option<String>,
+    user_agent: Option<String>,
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+}
+
+enum ApiKey {
+    Value(String),
+    Env(String),
+}
+
+impl ClientBuilder {
+    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(ApiKey::Value(api_key.into()));
+        self
+    }
+
+    /// Reads the API key from the environment variable `name` when building.
+    pub fn api_key_env(mut self, name: impl Into<String>) -> Self {
+        self.api_key = Some(ApiKey::Env(name.into()));
+        self
+    }
+
+    pub fn config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Adds a header sent with every request, e.g. OpenRouter's `HTTP-Referer` or `X-Title`.
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Routes all traffic through the proxy at `url`.
+    pub fn proxy(mut self, url: impl Into<String>) -> Self {
+        self.proxy = Some(url.into());
+        self
+    }
+
+    /// Defaults to `rgi/<version>`.
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Overrides [`Config::connect_timeout`].
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Timeout for each read from the connection, disabled by default.
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// # Errors
+    /// Returns `BuildError::MissingApiKey` if no API key was provided
+    /// Returns `BuildError::MissingEnv` if the API key variable is not set
+    /// Returns `BuildError::InvalidApiKey` or `BuildError::InvalidHeader` for values that can't be sent as a header
+    /// Returns `BuildError::Http` if the HTTP client can't be initialized
+    pub fn build(self) -> Result<Client> {
+        let api_key = match self.api_key {
+            Some(ApiKey::Value(api_key)) => api_key,
+            Some(ApiKey::Env(name)) => env::var(&name).map_err(|_| BuildError::MissingEnv(name))?,
+            None => return Err(BuildError::MissingApiKey.into()),
+        };
+
+        let mut authorization = HeaderValue::from_str(&format!("Bearer {}", api_key))
+            .map_err(|_| BuildError::InvalidApiKey)?;
+        authorization.set_sensitive(true);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        headers.insert(AUTHORIZATION, authorization);
+
+        for (name, value) in self.headers {
+            let (Ok(header_name), Ok(header_value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(&value),
+            ) else {
+                return Err(BuildError::InvalidHeader(name).into());
+            };
+            headers.insert(header_name, header_value);
+        }
+
+        let user_agent = self
+            .user_agent
+            .unwrap_or_else(|| format!("rgi/{}", env!("CARGO_PKG_VERSION")));
+
+        let mut http_client = HttpClient::builder()
+            .connect_timeout(self.connect_timeout.unwrap_or(self.config.connect_timeout))
+            .default_headers(headers)
+            .user_agent(user_agent);
+
+        if let Some(timeout) = self.read_timeout {
+            http_client = http_client.read_timeout(timeout);
+        }
+
+        if let Some(proxy) = self.proxy {
+            http_client = http_client.proxy(Proxy::all(proxy).map_err(BuildError::Http)?);
+        }
+
+        let http_client = http_client.build().map_err(BuildError::Http)?;
+
+        Ok(Client {
+            inner: Arc::new(ClientInner {
+                http: http_client,
+                config: self.config,
+            }),
+        })
+    }
+}
+
+/// Handle to the chat completion API.
+///
+/// Cloning is cheap and shares the underlying connection pool, so a single
+/// client can be handed to any number of tasks.
+#[derive(Clone)]
 pub struct Client {
-    pub inner: ClientWithMiddleware,
+    inner: Arc<ClientInner>,
+}
+
+struct ClientInner {
+    http: HttpClient,
     config: Config,
 }
 
 impl Client {
-    pub fn new(api_key: &str, config: Config) -> Self {
-        let mut headers = header::HeaderMap::new();
-        headers.insert("content-type", "application/json".parse().unwrap());
-        headers.insert(
-            "authorization",
-            format!("Bearer {}", api_key).parse().unwrap(),
-        );
-
-        let http_client = HttpClient::builder()
-            .timeout(config.connection_timeout)
-            .default_headers(headers)
-            .build()
-            .unwrap();
+    /// Creates a client with default settings.
+    ///
+    /// See [`Client::builder`] for reading the key from the environment,
+    /// extra headers, proxies and timeouts.
+    pub fn new(api_key: &str, config: Config) -> Result<Self> {
+        Self::builder().api_key(api_key).config(config).build()
+    }
 
-        let retry_policy = ExponentialBackoff::builder().build_with_max_retries(config.max_retries);
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::default()
+    }
 
-        let retry_client = ClientBuilder::new(http_client)
-            .with(RetryTransientMiddleware::new_with_policy(retry_policy))
-            .build();
+    pub fn config(&self) -> &Config {
+        &self.inner.config
+    }
 
-        Self {
-            inner: retry_client,
-            config,
+    pub async fn complete(&self, mut request: request::Chat) -> Result<completion::Object> {
+        request.stream = Some(false);
+        request.stream_options = None;
+        let request_url = self.prepare_chat(&mut request)?;
+
+        let (mut object, attempts) = self
+            .post::<completion::Object>(request_url, &request)
+            .await?;
+        object.attempts = attempts;
+
+        if self.config().split_think {
+            for choice in &mut object.choices {
+                split_think(&mut choice.message);
+            }
         }
+
+        Ok(object)
+    }
+
+    /// Completes `request` in JSON mode and deserializes the answer into `T`.
+    ///
+    /// If the answer isn't a valid `T`, the model is shown the parse error and
+    /// asked to correct its output, up to `max_repairs` times.
+    ///
+    /// # Errors
+    /// Returns `ResponseFormatError::MissingJsonPrompt` if no system or user message mentions JSON
+    /// Returns `Error::Decode` with the last answer if it still can't be deserialized
+    /// Returns any error of [`Client::complete`]
+    pub async fn complete_json<T: DeserializeOwned>(
+        &self,
+        mut request: request::Chat,
+        max_repairs: u32,
+    ) -> Result<T> {
+        if !request.mentions_json() {
+            return Err(request::ResponseFormatError::MissingJsonPrompt.into());
+        }
+
+        request.response_format = Some(request::ResponseFormat::JsonObject);
+        request.stream = Some(false);
+
+        let mut repairs = 0;
+
+        loop {
+            let object = self.complete(request.clone()).await?;
+
+            let content = object
+                .choices
+                .into_iter()
+                .next()
+                .and_then(|choice| choice.message.content)
+                .unwrap_or_default();
+
+            let source = match serde_json::from_str(&content) {
+                Ok(value) => return Ok(value),
+                Err(source) if repairs >= max_repairs => {
+                    return Err(Error::Decode {
+                        source,
+                        body: content,
+                    })
+                }
+                Err(source) => source,
+            };
+
+            repairs += 1;
+
+            request.messages.push(request::Message::Assistant {
+                content: Some(content),
+                name: None,
+                tool_calls: None,
+                prefix: None,
+                reasoning_content: None,
+            });
+            request.messages.push(request::Message::User {
+                content: format!(
+                    "Your reply could not be parsed: {}. Answer again with the corrected JSON only.",
+                    source
+                ),
+                name: None,
+            });
+        }
+    }
+
+    /// Streams the completion of `request`.
+    ///
+    /// Unless `stream_options.include_usage` is set explicitly, usage is requested
+    /// so the final chunk reports it like [`Client::complete`] does.
+    pub async fn stream(&self, mut request: request::Chat) -> Result<ChunkStream> {
+        request.stream = Some(true);
+        request
+            .stream_options
+            .get_or_insert_with(request::StreamOptions::default)
+            .include_usage
+            .get_or_insert(true);
+        let request_url = self.prepare_chat(&mut request)?;
+
+        let stream = self.open_stream(request_url, &request).await?;
+
+        match self.config().split_think {
+            true => Ok(stream.split_think()),
+            false => Ok(stream),
+        }
+    }
+
+    /// Completes the text between `prompt` and `suffix` through the beta
+    /// completions endpoint.
+    ///
+    /// # Errors
+    /// Returns `Error::Unsupported` if the provider has no beta endpoint
+    /// Returns any error of [`Client::complete`]
+    pub async fn complete_fim(
+        &self,
+        mut request: request::FimCompletion,
+    ) -> Result<completion::TextCompletion> {
+        request.stream = Some(false);
+        request.stream_options = None;
+        request
+            .model
+            .get_or_insert_with(|| self.config().model.clone());
+
+        let (mut object, attempts) = self
+            .post::<completion::TextCompletion>(self.fim_endpoint()?, &request)
+            .await?;
+        object.attempts = attempts;
+
+        Ok(object)
     }
-}
 
-pub async fn complete(
-    client: &Client,
-    request: request::Chat,
-) -> Result<completion::Object, Box<dyn std::error::Error>> {
-    let request_url = format!("{}/chat/completions", client.config.base_url);
+    /// Streams a FIM completion, see [`Client::complete_fim`].
+    pub async fn stream_fim(
+        &self,
+        mut request: request::FimCompletion,
+    ) -> Result<ChunkStream<completion::TextChunk>> {
+        request.stream = Some(true);
+        request
+            .model
+            .get_or_insert_with(|| self.config().model.clone());
+        request
+            .stream_options
+            .get_or_insert_with(request::StreamOptions::default)
+            .include_usage
+            .get_or_insert(true);
 
-    let body = json!(request);
+        self.open_stream(self.fim_endpoint()?, &request).await
+    }
+
+    /// Fills in defaults, validates `request` and picks its endpoint. Prefix
+    /// completions are only offered on the beta endpoint.
+    fn prepare_chat(&self, request: &mut request::Chat) -> Result<String> {
+        request
+            .model
+            .get_or_insert_with(|| self.config().model.clone());
+
+        if !self.config().openrouter {
+            request.openrouter = None;
+        }
+
+        request.validate_prefix()?;
+        request.validate_reasoning_content()?;
+        request.validate_reasoner()?;
+
+        if !request.is_prefix_completion() {
+            return Ok(self.config().endpoint(CHAT_COMPLETIONS));
+        }
+
+        self.config()
+            .beta_endpoint(CHAT_COMPLETIONS)
+            .ok_or(Error::Unsupported("chat prefix completions"))
+    }
+
+    fn fim_endpoint(&self) -> Result<String> {
+        self.config()
+            .beta_endpoint(COMPLETIONS)
+            .ok_or(Error::Unsupported("FIM completions"))
+    }
+
+    /// Posts `request` and decodes the response body as `T`.
+    async fn post<T: DeserializeOwned>(
+        &self,
+        request_url: String,
+        request: &impl Serialize,
+    ) -> Result<(T, u32)> {
+        let body = json!(request).to_string();
+
+        let (response, attempts) = self
+            .send(
+                || {
+                    self.inner
+                        .http
+                        .post(&request_url)
+                        .timeout(self.config().request_timeout)
+                        .body(body.clone())
+                },
+                None,
+            )
+            .await?;
+
+        let status = response.status();
+        let body = response.text().await?;
+
+        let object = serde_json::from_str(&body)
+            .map_err(|source| Error::from_body(Some(status), source, body))?;
+
+        Ok((object, attempts))
+    }
+
+    /// Posts `request` and streams the events of the response as `T`.
+    async fn open_stream<T>(
+        &self,
+        request_url: String,
+        request: &impl Serialize,
+    ) -> Result<ChunkStream<T>>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        let body = json!(request).to_string();
+
+        let idle_timeout = self.config().idle_timeout;
+
+        let (response, attempts) = self
+            .send(
+                || {
+                    self.inner
+                        .http
+                        .post(&request_url)
+                        .header("accept", "text/event-stream")
+                        .body(body.clone())
+                },
+                Some(idle_timeout),
+            )
+            .await?;
+
+        Ok(ChunkStream::new(
+            response.bytes_stream(),
+            idle_timeout,
+            attempts,
+        ))
+    }
+
+    /// Sends the request built by `request` until it succeeds or the retry policy gives up.
+    ///
+    /// Returns the successful response together with the number of attempts made.
+    async fn send(
+        &self,
+        request: impl Fn() -> RequestBuilder,
+        response_timeout: Option<Duration>,
+    ) -> Result<(Response, u32)> {
+        let start = Instant::now();
+        let mut attempts = 0;
 
-    let response = client
-        .inner
-        .post(request_url)
-        .body(body.to_string())
-        .send()
-        .await?
-        .json::<completion::Object>()
-        .await?;
+        loop {
+            attempts += 1;
 
-    Ok(response)
+            let response = match response_timeout {
+                Some(timeout) => time::timeout(timeout, request().send())
+                    .await
+                    .map_err(|_| StreamError::IdleTimeout(timeout)),
+                None => Ok(request().send().await),
+            };
+
+            let (failure, error) = match response {
+                Ok(Ok(response)) if response.status().is_success() => {
+                    return Ok((response, attempts))
+                }
+                Ok(Ok(response)) => (
+                    Failure::from_status(response.status(), response.headers()),
+                    status_error(response).await,
+                ),
+                Ok(Err(e)) => (Failure::from_transport(&e), e.into()),
+                Err(e) => (Failure::Timeout, e.into()),
+            };
+
+            let delay = self
+                .config()
+                .retry_policy
+                .retry_delay(&failure, attempts, start.elapsed());
+
+            match delay {
+                Some(delay) => time::sleep(delay).await,
+                None if attempts == 1 => return Err(error),
+                None => {
+                    return Err(Error::Retried {
+                        attempts,
+                        source: Box::new(error),
+                    })
+                }
+            }
+        }
+    }
 }
 
-pub async fn stream(
-    client: &Client,
-    request: request::Chat,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let request_url = format!("{}/api/v1/chat/completions", client.config.base_url);
+/// Moves `<think>` blocks from the content to the end of the reasoning.
+fn split_think(message: &mut completion::ResponseMessage) {
+    let Some(content) = message.content.take() else {
+        return;
+    };
 
-    let body = json!(request);
-    println!("Request: {}", body);
+    let split = ThinkSplitter::split(&content);
+    message.content = Some(split.content);
 
-    let mut response = client
-        .inner
-        .post(&request_url)
-        .header("accept", "text/event-stream")
-        .body(body.to_string())
-        .send()
-        .await?
-        .error_for_status()?;
+    if !split.reasoning.is_empty() {
+        message
+            .reasoning_content
+            .get_or_insert_with(String::new)
+            .push_str(&split.reasoning);
+    }
+}
 
-    let mut buffer = String::new();
+/// Turns a non-success response into the matching error.
+async fn status_error(response: Response) -> Error {
+    let status = response.status();
 
-    while let Some(chunk) = response.chunk().await? {
-        let chunk_str = String::from_utf8_lossy(&chunk);
-        buffer.push_str(&chunk_str);
+    let body = match response.text().await {
+        Ok(body) => body,
+        Err(e) => return e.into(),
+    };
 
-        while let Some(event_end) = buffer.find("\n\n") {
-            let event = buffer[..event_end].to_string();
-            buffer = buffer[event_end + 2..].to_string();
+    match serde_json::from_str::<completion::ErrorResponse>(&body) {
+        Ok(completion::ErrorResponse { error }) => Error::Api {
+            status: Some(status),
+            error,
+        },
+        Err(_) => Error::Status { status, body },
+    }
+}
 
-            for line in event.split('\n') {
-                if line.starts_with("data:") {
-                    let data = &line["data:".len()..];
+/// Completion chunks parsed from a server-sent event stream.
+///
+/// Yields every `data:` payload as a [`Chunk`], or a
+/// [`TextChunk`](completion::TextChunk) for FIM completions, and ends once the `[DONE]`
+/// sentinel is received. An `error` event, a chunk that fails to parse, or the
+/// connection closing before `[DONE]` is yielded as an error, after which the
+/// stream ends.
+pub struct ChunkStream<T = Chunk> {
+    inner: Pin<Box<dyn Stream<Item = Result<T>> + Send>>,
+    attempts: u32,
+}
+
+impl<T: DeserializeOwned + Send + 'static> ChunkStream<T> {
+    fn new<S>(bytes: S, idle_timeout: Duration, attempts: u32) -> Self
+    where
+        S: Stream<Item = reqwest::Result<Bytes>> + Send + 'static,
+    {
+        let state = StreamState {
+            bytes: Box::pin(bytes),
+            decoder: sse::Decoder::new(),
+            done: false,
+        };
+
+        let inner = stream::unfold(state, move |mut state| async move {
+            loop {
+                if state.done {
+                    return None;
+                }
 
-                    if data.trim() == "[DONE]" {
-                        return Ok(());
+                if let Some(event) = state.decoder.next_event() {
+                    if event.data.trim() == "[DONE]" {
+                        return None;
                     }
 
-                    let chunk = match serde_json::from_str::<Chunk>(data) {
-                        Ok(c) => c,
-                        Err(e) => {
-                            println!("data: {}\n", data);
-                            println!("Error parsing chunk: {}", e);
-                            return Ok(());
+                    // Errors after the response started arrive as events, possibly
+                    // next to fields that would parse as a regular chunk. Only events
+                    // mentioning an `error` key are tried as an envelope.
+                    let envelope = match event.data.contains("\"error\"") {
+                        true => serde_json::from_str(&event.data).ok(),
+                        false => None,
+                    };
+
+                    let chunk = match envelope {
+                        Some(completion::ErrorResponse { error }) => Err(Error::Api {
+                            status: None,
+                            error,
+                        }),
+                        None => {
+                            serde_json::from_str::<T>(&event.data).map_err(|source| Error::Decode {
+                                source,
+                                body: event.data,
+                            })
                         }
                     };
-                    println!("{:#?}", chunk)
+                    state.done = chunk.is_err();
+                    return Some((chunk, state));
+                }
+
+                let Ok(bytes) = time::timeout(idle_timeout, state.bytes.next()).await else {
+                    state.done = true;
+                    return Some((Err(StreamError::IdleTimeout(idle_timeout).into()), state));
+                };
+
+                match bytes {
+                    Some(Ok(bytes)) => state.decoder.feed(&bytes),
+                    Some(Err(e)) => {
+                        state.done = true;
+                        return Some((Err(e.into()), state));
+                    }
+                    None => {
+                        state.done = true;
+                        return Some((Err(StreamError::UnexpectedEof.into()), state));
+                    }
                 }
             }
+        });
+
+        Self {
+            inner: Box::pin(inner),
+            attempts,
         }
     }
 
-    Ok(())
+    /// Number of requests it took until the stream started.
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+}
+
+impl ChunkStream {
+    /// Moves `<think>` blocks from every choice's content deltas to its
+    /// reasoning deltas. Held back text is released with the `finish_reason`,
+    /// or in a final chunk if the stream ends without one.
+    fn split_think(self) -> Self {
+        let state = ThinkState {
+            inner: self.inner,
+            splitters: BTreeMap::new(),
+            last: None,
+        };
+
+        let inner = stream::unfold(Some(state), |state| async move {
+            let mut state = state?;
+
+            let Some(chunk) = state.inner.next().await else {
+                return state.flush().map(|chunk| (Ok(chunk), None));
+            };
+
+            let chunk = chunk.map(|mut chunk| {
+                for choice in &mut chunk.choices {
+                    let splitter = state.splitters.entry(choice.index).or_default();
+                    let delta = &mut choice.delta;
+
+                    let mut split = splitter.push(delta.content.as_deref().unwrap_or_default());
+                    if choice.finish_reason.is_some() {
+                        split.extend(splitter.finish());
+                    }
+
+                    if delta.content.is_some() || !split.content.is_empty() {
+                        delta.content = Some(split.content);
+                    }
+
+                    if !split.reasoning.is_empty() {
+                        delta
+                            .reasoning_content
+                            .get_or_insert_with(String::new)
+                            .push_str(&split.reasoning);
+                    }
+                }
+
+                state.last = Some((chunk.id.clone(), chunk.created, chunk.model.clone()));
+                chunk
+            });
+
+            Some((chunk, Some(state)))
+        });
+
+        Self {
+            inner: Box::pin(inner),
+            attempts: self.attempts,
+        }
+    }
+
+    /// Consumes the stream and folds all chunks into a single completion.
+    pub async fn collect_completion(mut self) -> Result<completion::Object> {
+        let mut accumulator = ChunkAccumulator::new();
+
+        while let Some(chunk) = self.next().await {
+            accumulator.push(chunk?);
+        }
+
+        let mut object = accumulator.finish()?;
+        object.attempts = self.attempts;
+
+        Ok(object)
+    }
+}
+
+impl<T> Stream for ChunkStream<T> {
+    type Item = Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+struct StreamState {
+    bytes: Pin<Box<dyn Stream<Item = reqwest::Result<Bytes>> + Send>>,
+    decoder: sse::Decoder,
+    done: bool,
+}
+
+struct ThinkState {
+    inner: Pin<Box<dyn Stream<Item = Result<Chunk>> + Send>>,
+    splitters: BTreeMap<u32, ThinkSplitter>,
+    /// `id`, `created` and `model` of the last chunk, repeated by the final one.
+    last: Option<(String, u32, String)>,
+}
+
+impl ThinkState {
+    /// A chunk releasing the text still held back, if there is any.
+    fn flush(mut self) -> Option<Chunk> {
+        let (id, created, model) = self.last.take()?;
+
+        let choices: Vec<_> = self
+            .splitters
+            .iter_mut()
+            .filter_map(|(&index, splitter)| {
+                let split = splitter.finish();
+                if split == Split::default() {
+                    return None;
+                }
+
+                let some = |text: String| (!text.is_empty()).then_some(text);
+
+                Some(completion::StreamChoice {
+                    delta: completion::Delta {
+                        content: some(split.content),
+                        reasoning_content: some(split.reasoning),
+                        tool_calls: None,
+                        role: None,
+                    },
+                    finish_reason: None,
+                    index,
+                    logprobs: None,
+                })
+            })
+            .collect();
+
+        if choices.is_empty() {
+            return None;
+        }
+
+        Some(Chunk {
+            id,
+            choices,
+            created,
+            model,
+            system_fingerprint: None,
+            object: completion::ResponseObject::ChatCompletionChunk,
+            usage: None,
+            provider: None,
+        })
+    }
 }
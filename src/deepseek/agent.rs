@@ -0,0 +1,236 @@
+//! Running chat and tool-call cycles until the model is done.
+//!
+//! An [`Agent`] sends the conversation, executes the tool calls of every
+//! response through a [`ToolRegistry`], appends the assistant message and the
+//! tool replies, and asks again until the model stops on its own.
+//!
+//! # Examples
+//! ```no_run
+//! # use rgi::deepseek::agent::Agent;
+//! # use rgi::deepseek::request::{Chat, Message};
+//! # use rgi::deepseek::tools::ToolRegistry;
+//! # use rgi::deepseek::Client;
+//! # #[tokio::main]
+//! # async fn main() -> rgi::Result<()> {
+//! let client = Client::builder().api_key_env("DEEPSEEK_KEY").build()?;
+//! let tools = ToolRegistry::new();
+//!
+//! let agent = Agent::new(client, tools)
+//!     .max_steps(8)
+//!     .on_step(|step| println!("step {}: {:?}", step.index, step.tool_calls()));
+//!
+//! let transcript = agent
+//!     .run(Chat {
+//!         messages: vec![Message::User {
+//!             content: String::from("How's the weather in Hangzhou?"),
+//!             name: None,
+//!         }],
+//!         ..Chat::default()
+//!     })
+//!     .await?;
+//!
+//! println!("{:?}", transcript.content());
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::completion::{FinishReason, Object, ToolCall};
+use super::request::{Chat, Message};
+use super::tools::ToolRegistry;
+use super::Client;
+use crate::{AgentError, Result};
+
+type Hook = Arc<dyn Fn(&Step) + Send + Sync>;
+
+/// Drives a conversation with tools until the model answers with
+/// [`FinishReason::Stop`].
+///
+/// Cloning is cheap, the client, tools and hooks are shared.
+#[derive(Clone)]
+pub struct Agent {
+    client: Client,
+    tools: ToolRegistry,
+    max_steps: usize,
+    parallel_tool_calls: bool,
+    hooks: Vec<Hook>,
+}
+
+impl Agent {
+    /// Defaults to at most 10 steps with tool calls executed concurrently.
+    pub fn new(client: Client, tools: ToolRegistry) -> Self {
+        Self {
+            client,
+            tools,
+            max_steps: 10,
+            parallel_tool_calls: true,
+            hooks: Vec::new(),
+        }
+    }
+
+    /// Upper bound on the number of completions requested by a single run.
+    pub fn max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// Runs the tool calls of one response concurrently (the default) or in order.
+    pub fn parallel_tool_calls(mut self, parallel_tool_calls: bool) -> Self {
+        self.parallel_tool_calls = parallel_tool_calls;
+        self
+    }
+
+    /// Calls `hook` after every step, in the order hooks were added.
+    pub fn on_step(mut self, hook: impl Fn(&Step) + Send + Sync + 'static) -> Self {
+        self.hooks.push(Arc::new(hook));
+        self
+    }
+
+    pub fn tools(&self) -> &ToolRegistry {
+        &self.tools
+    }
+
+    /// Runs `chat` to completion.
+    ///
+    /// The registered tools are offered unless `chat.tools` is already set,
+    /// and `chat.stream` is turned off.
+    ///
+    /// # Errors
+    /// Returns `AgentError::MaxSteps` if the model still calls tools after `max_steps` completions
+    /// Returns `AgentError::Stopped` if the model stops for any reason other than `Stop` or `ToolCalls`
+    /// Returns `AgentError::EmptyResponse` if a completion has no choices
+    /// Returns any error of [`Client::complete`]
+    pub async fn run(&self, mut chat: Chat) -> Result<Transcript> {
+        if chat.tools.is_none() && !self.tools.is_empty() {
+            chat.tools = Some(self.tools.definitions());
+        }
+        chat.stream = Some(false);
+
+        let mut transcript = Transcript {
+            messages: chat.messages.clone(),
+            steps: Vec::new(),
+        };
+
+        for index in 0..self.max_steps {
+            let started = Instant::now();
+
+            chat.messages = transcript.messages.clone();
+            let completion = self.client.complete(chat.clone()).await?;
+
+            let Some(choice) = completion.choices.first() else {
+                return Err(AgentError::EmptyResponse.into());
+            };
+
+            let finish_reason = choice.finish_reason;
+            let tool_calls = choice.message.tool_calls.clone().unwrap_or_default();
+
+            transcript
+                .messages
+                .push(Message::from(choice.message.clone()));
+
+            let tool_replies = if self.parallel_tool_calls {
+                self.tools.execute_concurrently(&tool_calls).await
+            } else {
+                self.tools.execute_all(&tool_calls).await
+            };
+            transcript.messages.extend(tool_replies.iter().cloned());
+
+            let step = Step {
+                index,
+                completion,
+                tool_replies,
+                elapsed: started.elapsed(),
+            };
+
+            for hook in &self.hooks {
+                hook(&step);
+            }
+
+            transcript.steps.push(step);
+
+            match finish_reason {
+                _ if !tool_calls.is_empty() => continue,
+                FinishReason::Stop => return Ok(transcript),
+                FinishReason::ToolCalls => continue,
+                reason => {
+                    return Err(AgentError::Stopped {
+                        reason,
+                        transcript: Box::new(transcript),
+                    }
+                    .into())
+                }
+            }
+        }
+
+        Err(AgentError::MaxSteps {
+            max_steps: self.max_steps,
+            transcript: Box::new(transcript),
+        }
+        .into())
+    }
+}
+
+impl fmt::Debug for Agent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Agent")
+            .field("tools", &self.tools)
+            .field("max_steps", &self.max_steps)
+            .field("parallel_tool_calls", &self.parallel_tool_calls)
+            .field("hooks", &self.hooks.len())
+            .finish()
+    }
+}
+
+/// One completion and the tool calls it triggered.
+#[derive(Debug)]
+pub struct Step {
+    /// Position within the run, starting at 0.
+    pub index: usize,
+    pub completion: Object,
+    /// Replies to the tool calls of `completion`, in the same order.
+    pub tool_replies: Vec<Message>,
+    /// Time spent on the completion and the tool calls.
+    pub elapsed: Duration,
+}
+
+impl Step {
+    pub fn tool_calls(&self) -> &[ToolCall] {
+        self.completion
+            .choices
+            .first()
+            .and_then(|choice| choice.message.tool_calls.as_deref())
+            .unwrap_or_default()
+    }
+}
+
+/// Everything that happened during a run.
+#[derive(Debug, Default)]
+pub struct Transcript {
+    /// The conversation, starting with the messages of the initial request.
+    pub messages: Vec<Message>,
+    pub steps: Vec<Step>,
+}
+
+impl Transcript {
+    /// Content of the last assistant message.
+    pub fn content(&self) -> Option<&str> {
+        self.messages
+            .iter()
+            .rev()
+            .find_map(|message| match message {
+                Message::Assistant { content, .. } => content.as_deref(),
+                _ => None,
+            })
+    }
+
+    /// Total tokens used across all steps, as reported by the provider.
+    pub fn total_tokens(&self) -> u32 {
+        self.steps
+            .iter()
+            .filter_map(|step| step.completion.usage.total_tokens)
+            .sum()
+    }
+}
@@ -1,4 +1,8 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::StreamError;
 
 #[derive(Debug, Deserialize)]
 pub struct Object {
@@ -6,9 +10,15 @@ pub struct Object {
     pub choices: Vec<Choice>,
     pub created: u32,
     pub model: String,
-    pub system_fingerprint: String,
+    pub system_fingerprint: Option<String>,
     pub object: ResponseObject,
     pub usage: Usage,
+    /// Upstream provider that served the request, only reported by OpenRouter.
+    pub provider: Option<String>,
+    /// Number of requests the client sent to obtain this completion, including
+    /// retries. Not part of the response body and `0` if it wasn't received through a client.
+    #[serde(skip)]
+    pub attempts: u32,
 }
 
 #[derive(Debug, Deserialize)]
@@ -16,10 +26,11 @@ pub struct Choice {
     pub finish_reason: FinishReason,
     pub index: u32,
     pub message: ResponseMessage,
-    // TODO: logprobs
+    /// Present if the request asked for [`LogProbs`](super::request::LogProbs).
+    pub logprobs: Option<LogProbs>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum FinishReason {
     Stop,
@@ -29,30 +40,29 @@ pub enum FinishReason {
     InsufficientSystemResource,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct ResponseMessage {
     pub content: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reasoning_content: Option<String>,
-    // TODO: Implement tool calls
-    // pub tool_calls: Vec<ToolCall>,
+    pub tool_calls: Option<Vec<ToolCall>>,
     pub role: Role,
 }
 
-// #[derive(Debug, Deserialize)]
-// pub struct ToolCall {
-//     pub id: String,
-//     #[serde(rename = "type")]
-//     pub type_: ToolType,
-//     pub function: FunctionCall,
-// }
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ToolCall {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub type_: ToolType,
+    pub function: FunctionCall,
+}
 
-// #[derive(Debug, Deserialize)]
-// pub struct FunctionCall {
-//     pub name: String,
-//     // Potentially JSON
-//     pub arguments: String,
-// }
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FunctionCall {
+    pub name: String,
+    // Potentially JSON
+    pub arguments: String,
+}
 
 #[derive(Debug, Default, Deserialize)]
 pub enum ResponseObject {
@@ -61,23 +71,72 @@ pub enum ResponseObject {
     ChatCompletion,
     #[serde(rename = "chat.completion.chunk")]
     ChatCompletionChunk,
+    #[serde(rename = "text_completion")]
+    TextCompletion,
 }
 
-#[derive(Debug, Default, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum Role {
     #[default]
     Assistant,
 }
 
-#[derive(Debug, Default, Deserialize)]
+/// Log probabilities of the generated tokens.
+///
+/// # Examples
+/// ```
+/// # use rgi::deepseek::completion::LogProbs;
+/// let logprobs: LogProbs = serde_json::from_str(r#"{"content":[{
+///     "token":"Hello","logprob":-0.0001,"bytes":[72,101,108,108,111],
+///     "top_logprobs":[
+///         {"token":"Hello","logprob":-0.0001,"bytes":[72,101,108,108,111]},
+///         {"token":"Hi","logprob":-9.5,"bytes":[72,105]}
+///     ]
+/// }]}"#).unwrap();
+///
+/// let token = &logprobs.content.unwrap()[0];
+/// assert_eq!(token.token, "Hello");
+/// assert_eq!(token.top_logprobs[1].token, "Hi");
+/// assert!(token.probability() > 0.99);
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LogProbs {
+    pub content: Option<Vec<TokenLogProb>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TokenLogProb {
+    pub token: String,
+    pub logprob: f64,
+    /// UTF-8 bytes of the token, `None` if the token has no byte representation.
+    pub bytes: Option<Vec<u8>>,
+    /// The most likely tokens at this position, as many as `top_logprobs` requested.
+    #[serde(default)]
+    pub top_logprobs: Vec<TopLogProb>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TopLogProb {
+    pub token: String,
+    pub logprob: f64,
+    pub bytes: Option<Vec<u8>>,
+}
+
+impl TokenLogProb {
+    pub fn probability(&self) -> f64 {
+        self.logprob.exp()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum ToolType {
     #[default]
     Function,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct Usage {
     pub completion_tokens: Option<u32>,
     pub prompt_tokens: Option<u32>,
@@ -94,6 +153,65 @@ pub struct Usage {
 //     pub reasoning_tokens: u32,
 // }
 
+/// Error details returned by DeepSeek and OpenRouter, both in non-2xx response
+/// bodies and as in-band stream events.
+///
+/// # Examples
+/// ```
+/// # use rgi::deepseek::completion::{ErrorCode, ErrorResponse};
+/// let deepseek = r#"{"error":{"message":"Insufficient Balance","type":"unknown_error",
+///     "param":null,"code":"invalid_request_error"}}"#;
+/// let ErrorResponse { error } = serde_json::from_str(deepseek).unwrap();
+/// assert_eq!(error.code, Some(ErrorCode::Text(String::from("invalid_request_error"))));
+///
+/// let openrouter = r#"{"error":{"code":429,"message":"Rate limit exceeded",
+///     "metadata":{"provider_name":"Together"}}}"#;
+/// let ErrorResponse { error } = serde_json::from_str(openrouter).unwrap();
+/// assert_eq!(error.code, Some(ErrorCode::Number(429)));
+/// assert_eq!(error.to_string(), "Rate limit exceeded (429)");
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApiError {
+    pub code: Option<ErrorCode>,
+    pub message: String,
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
+    /// OpenRouter attaches details such as the upstream provider's raw error here.
+    pub metadata: Option<serde_json::Value>,
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.code {
+            Some(code) => write!(f, "{} ({})", self.message, code),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+/// DeepSeek reports string codes, OpenRouter mirrors the HTTP status as a number.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum ErrorCode {
+    Number(i64),
+    Text(String),
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErrorCode::Number(code) => write!(f, "{}", code),
+            ErrorCode::Text(code) => f.write_str(code),
+        }
+    }
+}
+
+/// The `{"error": {...}}` envelope wrapping an [`ApiError`].
+#[derive(Debug, Deserialize)]
+pub struct ErrorResponse {
+    pub error: ApiError,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct Chunk {
     pub id: String,
@@ -103,13 +221,16 @@ pub struct Chunk {
     pub system_fingerprint: Option<String>,
     pub object: ResponseObject,
     pub usage: Option<Usage>,
+    pub provider: Option<String>,
 }
+
 #[derive(Debug, Deserialize)]
 pub struct StreamChoice {
     pub delta: Delta,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub finish_reason: Option<FinishReason>,
     pub index: u32,
+    pub logprobs: Option<LogProbs>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -119,5 +240,257 @@ pub struct Delta {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reasoning_content: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<ToolCallDelta>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub role: Option<Role>,
 }
+
+/// A fragment of a [`ToolCall`]. The first fragment of every call carries its
+/// `id` and function name, later ones append to the arguments.
+#[derive(Debug, Deserialize)]
+pub struct ToolCallDelta {
+    pub index: u32,
+    pub id: Option<String>,
+    #[serde(rename = "type")]
+    pub type_: Option<ToolType>,
+    pub function: Option<FunctionCallDelta>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FunctionCallDelta {
+    pub name: Option<String>,
+    pub arguments: Option<String>,
+}
+
+/// Response to a [`FimCompletion`](super::request::FimCompletion).
+#[derive(Debug, Deserialize)]
+pub struct TextCompletion {
+    pub id: String,
+    pub choices: Vec<TextChoice>,
+    pub created: u32,
+    pub model: String,
+    pub system_fingerprint: Option<String>,
+    pub object: ResponseObject,
+    pub usage: Usage,
+    /// Number of requests the client sent to obtain this completion, including
+    /// retries. Not part of the response body and `0` if it wasn't received through a client.
+    #[serde(skip)]
+    pub attempts: u32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TextChoice {
+    pub finish_reason: FinishReason,
+    pub index: u32,
+    pub text: String,
+    pub logprobs: Option<TextLogProbs>,
+}
+
+/// A streamed part of a [`TextCompletion`].
+#[derive(Debug, Deserialize)]
+pub struct TextChunk {
+    pub id: String,
+    pub choices: Vec<TextStreamChoice>,
+    pub created: u32,
+    pub model: String,
+    pub system_fingerprint: Option<String>,
+    pub object: ResponseObject,
+    pub usage: Option<Usage>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TextStreamChoice {
+    pub finish_reason: Option<FinishReason>,
+    pub index: u32,
+    pub text: String,
+    pub logprobs: Option<TextLogProbs>,
+}
+
+/// Log probabilities in the legacy completions format, one entry per token.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TextLogProbs {
+    #[serde(default)]
+    pub tokens: Vec<String>,
+    #[serde(default)]
+    pub token_logprobs: Vec<f64>,
+    /// The most likely tokens at every position, keyed by token.
+    #[serde(default)]
+    pub top_logprobs: Vec<BTreeMap<String, f64>>,
+    /// Character offset of every token in the text.
+    #[serde(default)]
+    pub text_offset: Vec<u32>,
+}
+
+/// Folds streamed [`Chunk`]s back into the [`Object`] a non-streaming request returns.
+///
+/// Deltas and log probabilities are concatenated per [`StreamChoice::index`], the last `finish_reason`
+/// of every choice is kept and the usage reported by the final chunk is attached.
+///
+/// # Examples
+/// ```
+/// # use rgi::deepseek::completion::{Chunk, ChunkAccumulator, FinishReason};
+/// let chunks = [
+///     r#"{"id":"1","created":0,"model":"deepseek-chat","object":"chat.completion.chunk",
+///         "choices":[{"index":0,"delta":{"role":"assistant","content":"Hel"}}]}"#,
+///     r#"{"id":"1","created":0,"model":"deepseek-chat","object":"chat.completion.chunk",
+///         "choices":[{"index":0,"delta":{"content":"lo"},"finish_reason":"stop"}]}"#,
+///     r#"{"id":"1","created":0,"model":"deepseek-chat","object":"chat.completion.chunk",
+///         "choices":[],"usage":{"prompt_tokens":3,"completion_tokens":2,"total_tokens":5}}"#,
+/// ];
+///
+/// let mut accumulator = ChunkAccumulator::new();
+/// for chunk in chunks {
+///     accumulator.push(serde_json::from_str::<Chunk>(chunk).unwrap());
+/// }
+///
+/// let object = accumulator.finish().unwrap();
+/// assert_eq!(object.choices[0].message.content.as_deref(), Some("Hello"));
+/// assert!(matches!(object.choices[0].finish_reason, FinishReason::Stop));
+/// assert_eq!(object.usage.total_tokens, Some(5));
+/// ```
+#[derive(Debug, Default)]
+pub struct ChunkAccumulator {
+    head: Option<ChunkHead>,
+    choices: BTreeMap<u32, ChoiceAccumulator>,
+    usage: Option<Usage>,
+}
+
+#[derive(Debug)]
+struct ChunkHead {
+    id: String,
+    created: u32,
+    model: String,
+    system_fingerprint: Option<String>,
+    provider: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct ChoiceAccumulator {
+    content: Option<String>,
+    reasoning_content: Option<String>,
+    tool_calls: BTreeMap<u32, ToolCall>,
+    logprobs: Option<Vec<TokenLogProb>>,
+    finish_reason: Option<FinishReason>,
+}
+
+impl ChunkAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, chunk: Chunk) {
+        let head = self.head.get_or_insert(ChunkHead {
+            id: chunk.id,
+            created: chunk.created,
+            model: chunk.model,
+            system_fingerprint: None,
+            provider: None,
+        });
+
+        if chunk.system_fingerprint.is_some() {
+            head.system_fingerprint = chunk.system_fingerprint;
+        }
+
+        if chunk.provider.is_some() {
+            head.provider = chunk.provider;
+        }
+
+        if chunk.usage.is_some() {
+            self.usage = chunk.usage;
+        }
+
+        for choice in chunk.choices {
+            let accumulated = self.choices.entry(choice.index).or_default();
+
+            append(&mut accumulated.content, choice.delta.content);
+            append(
+                &mut accumulated.reasoning_content,
+                choice.delta.reasoning_content,
+            );
+
+            for delta in choice.delta.tool_calls.into_iter().flatten() {
+                let tool_call = accumulated.tool_calls.entry(delta.index).or_default();
+
+                if let Some(id) = delta.id {
+                    tool_call.id = id;
+                }
+
+                if let Some(type_) = delta.type_ {
+                    tool_call.type_ = type_;
+                }
+
+                if let Some(function) = delta.function {
+                    if let Some(name) = function.name {
+                        tool_call.function.name = name;
+                    }
+                    if let Some(arguments) = function.arguments {
+                        tool_call.function.arguments.push_str(&arguments);
+                    }
+                }
+            }
+
+            if let Some(content) = choice.logprobs.and_then(|logprobs| logprobs.content) {
+                accumulated
+                    .logprobs
+                    .get_or_insert_with(Vec::new)
+                    .extend(content);
+            }
+
+            if choice.finish_reason.is_some() {
+                accumulated.finish_reason = choice.finish_reason;
+            }
+        }
+    }
+
+    /// Builds the completion from all chunks pushed so far.
+    ///
+    /// # Errors
+    /// Returns `StreamError::Empty` if no chunk was pushed
+    /// Returns `StreamError::MissingFinishReason` if a choice never received a `finish_reason`
+    pub fn finish(self) -> Result<Object, StreamError> {
+        let head = self.head.ok_or(StreamError::Empty)?;
+
+        let choices = self
+            .choices
+            .into_iter()
+            .map(|(index, choice)| {
+                Ok(Choice {
+                    finish_reason: choice
+                        .finish_reason
+                        .ok_or(StreamError::MissingFinishReason(index))?,
+                    index,
+                    message: ResponseMessage {
+                        content: choice.content,
+                        reasoning_content: choice.reasoning_content,
+                        tool_calls: Some(choice.tool_calls.into_values().collect())
+                            .filter(|tool_calls: &Vec<_>| !tool_calls.is_empty()),
+                        role: Role::Assistant,
+                    },
+                    logprobs: choice.logprobs.map(|content| LogProbs {
+                        content: Some(content),
+                    }),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Object {
+            id: head.id,
+            choices,
+            created: head.created,
+            model: head.model,
+            system_fingerprint: head.system_fingerprint,
+            object: ResponseObject::ChatCompletion,
+            usage: self.usage.unwrap_or_default(),
+            provider: head.provider,
+            attempts: 0,
+        })
+    }
+}
+
+fn append(target: &mut Option<String>, delta: Option<String>) {
+    match (target.as_mut(), delta) {
+        (Some(target), Some(delta)) => target.push_str(&delta),
+        (None, delta @ Some(_)) => *target = delta,
+        (_, None) => {}
+    }
+}
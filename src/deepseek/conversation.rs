@@ -0,0 +1,106 @@
+//! Multi-turn conversations with reasoning models.
+//!
+//! `deepseek-reasoner` returns its chain of thought as `reasoning_content`, but
+//! rejects requests that send it back. A [`Conversation`] keeps the reasoning
+//! of every answer next to the message and only hands the messages to requests.
+//!
+//! # Examples
+//! ```
+//! # use rgi::deepseek::completion::ResponseMessage;
+//! # use rgi::deepseek::conversation::Conversation;
+//! # use rgi::deepseek::request::Chat;
+//! let mut conversation = Conversation::new();
+//! conversation.user("9.11 and 9.8, which is greater?");
+//!
+//! let answer: ResponseMessage = serde_json::from_str(
+//!     r#"{"role":"assistant","content":"9.8","reasoning_content":"Compare the tenths..."}"#,
+//! )
+//! .unwrap();
+//! conversation.push_response(answer);
+//! conversation.user("How many Rs are there in 'strawberry'?");
+//!
+//! assert_eq!(conversation.reasoning().count(), 1);
+//! assert_eq!(conversation.last_reasoning(), Some("Compare the tenths..."));
+//!
+//! let chat = Chat {
+//!     messages: conversation.messages(),
+//!     ..Chat::default()
+//! };
+//! assert!(!serde_json::to_string(&chat).unwrap().contains("reasoning_content"));
+//! ```
+
+use super::completion::ResponseMessage;
+use super::request::Message;
+
+/// A message and the reasoning that led to it.
+#[derive(Debug, Clone)]
+pub struct Turn {
+    pub message: Message,
+    /// `None` for messages that weren't produced by a reasoning model.
+    pub reasoning: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Conversation {
+    turns: Vec<Turn>,
+}
+
+impl Conversation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn system(&mut self, content: impl Into<String>) -> &mut Self {
+        self.push(Message::System {
+            content: content.into(),
+            name: None,
+        })
+    }
+
+    pub fn user(&mut self, content: impl Into<String>) -> &mut Self {
+        self.push(Message::User {
+            content: content.into(),
+            name: None,
+        })
+    }
+
+    pub fn push(&mut self, message: Message) -> &mut Self {
+        self.turns.push(Turn {
+            message,
+            reasoning: None,
+        });
+        self
+    }
+
+    /// Appends an answer, keeping its reasoning out of the message.
+    pub fn push_response(&mut self, mut response: ResponseMessage) -> &mut Self {
+        let reasoning = response.reasoning_content.take();
+
+        self.turns.push(Turn {
+            message: response.into(),
+            reasoning,
+        });
+        self
+    }
+
+    pub fn turns(&self) -> &[Turn] {
+        &self.turns
+    }
+
+    /// The messages to send, without any reasoning.
+    pub fn messages(&self) -> Vec<Message> {
+        self.turns.iter().map(|turn| turn.message.clone()).collect()
+    }
+
+    /// Reasoning of every answer that had any, oldest first.
+    pub fn reasoning(&self) -> impl Iterator<Item = &str> {
+        self.turns
+            .iter()
+            .filter_map(|turn| turn.reasoning.as_deref())
+    }
+
+    /// Reasoning of the most recent answer that had any.
+    pub fn last_reasoning(&self) -> Option<&str> {
+        self.reasoning().last()
+    }
+}
@@ -0,0 +1,133 @@
+//! Models served by DeepSeek and by OpenRouter under DeepSeek's name.
+//!
+//! # Examples
+//! ```
+//! # use rgi::deepseek::Model;
+//! assert_eq!(Model::Chat.as_str(), "deepseek-chat");
+//! assert_eq!(Model::from("deepseek/deepseek-r1"), Model::OpenRouterR1);
+//! assert_eq!(
+//!     Model::from("deepseek/deepseek-v3-base"),
+//!     Model::Custom(String::from("deepseek/deepseek-v3-base"))
+//! );
+//!
+//! let reasoner = Model::Reasoner.capabilities().unwrap();
+//! assert!(reasoner.reasoning && !reasoner.tools);
+//! ```
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Model {
+    /// `deepseek-chat` on the DeepSeek API.
+    Chat,
+    /// `deepseek-reasoner` on the DeepSeek API.
+    Reasoner,
+    /// `deepseek/deepseek-chat` on OpenRouter.
+    OpenRouterChat,
+    /// `deepseek/deepseek-r1` on OpenRouter.
+    OpenRouterR1,
+    /// `deepseek/deepseek-r1-distill-llama-70b` on OpenRouter.
+    OpenRouterR1DistillLlama70b,
+    /// Any other model identifier, sent as is.
+    Custom(String),
+}
+
+/// What a model supports, as documented by its provider.
+///
+/// Token counts vary between OpenRouter's upstream providers, the values given
+/// are those of the model's reference deployment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    /// Maximum number of input and output tokens combined.
+    pub context_window: u32,
+    /// Maximum number of tokens of the final answer.
+    pub max_output: u32,
+    pub tools: bool,
+    pub json_mode: bool,
+    /// Whether the model returns `reasoning_content`.
+    pub reasoning: bool,
+}
+
+impl Model {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Model::Chat => "deepseek-chat",
+            Model::Reasoner => "deepseek-reasoner",
+            Model::OpenRouterChat => "deepseek/deepseek-chat",
+            Model::OpenRouterR1 => "deepseek/deepseek-r1",
+            Model::OpenRouterR1DistillLlama70b => "deepseek/deepseek-r1-distill-llama-70b",
+            Model::Custom(model) => model,
+        }
+    }
+
+    /// `None` for [`Model::Custom`].
+    pub const fn capabilities(&self) -> Option<Capabilities> {
+        let capabilities = match self {
+            Model::Chat | Model::OpenRouterChat => Capabilities {
+                context_window: 64_000,
+                max_output: 8_192,
+                tools: true,
+                json_mode: true,
+                reasoning: false,
+            },
+            Model::Reasoner | Model::OpenRouterR1 => Capabilities {
+                context_window: 64_000,
+                max_output: 8_192,
+                tools: false,
+                json_mode: false,
+                reasoning: true,
+            },
+            Model::OpenRouterR1DistillLlama70b => Capabilities {
+                context_window: 131_072,
+                max_output: 16_384,
+                tools: false,
+                json_mode: false,
+                reasoning: true,
+            },
+            Model::Custom(_) => return None,
+        };
+
+        Some(capabilities)
+    }
+}
+
+impl fmt::Display for Model {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<&str> for Model {
+    fn from(model: &str) -> Self {
+        match model {
+            "deepseek-chat" => Model::Chat,
+            "deepseek-reasoner" => Model::Reasoner,
+            "deepseek/deepseek-chat" => Model::OpenRouterChat,
+            "deepseek/deepseek-r1" => Model::OpenRouterR1,
+            "deepseek/deepseek-r1-distill-llama-70b" => Model::OpenRouterR1DistillLlama70b,
+            model => Model::Custom(String::from(model)),
+        }
+    }
+}
+
+impl From<String> for Model {
+    fn from(model: String) -> Self {
+        match Model::from(model.as_str()) {
+            Model::Custom(_) => Model::Custom(model),
+            known => known,
+        }
+    }
+}
+
+impl Serialize for Model {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Model {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Model::from)
+    }
+}
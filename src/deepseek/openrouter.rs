@@ -0,0 +1,122 @@
+//! Request fields only OpenRouter understands.
+//!
+//! Set [`Chat::openrouter`](super::request::Chat::openrouter) to route a
+//! request across providers, fall back to other models or control reasoning.
+//! The fields are dropped for services other than OpenRouter, see
+//! [`Config::openrouter`](super::Config::openrouter).
+//!
+//! # Examples
+//! ```
+//! # use rgi::deepseek::openrouter::{DataCollection, Effort, OpenRouter, Provider, Reasoning};
+//! # use rgi::deepseek::request::Chat;
+//! # use rgi::deepseek::Model;
+//! # use serde_json::json;
+//! let chat = Chat {
+//!     model: Some(Model::OpenRouterR1),
+//!     openrouter: Some(OpenRouter {
+//!         provider: Some(Provider {
+//!             order: Some(vec![String::from("DeepSeek"), String::from("Together")]),
+//!             data_collection: Some(DataCollection::Deny),
+//!             ..Provider::default()
+//!         }),
+//!         models: Some(vec![Model::OpenRouterChat]),
+//!         reasoning: Some(Reasoning {
+//!             effort: Some(Effort::High),
+//!             ..Reasoning::default()
+//!         }),
+//!         ..OpenRouter::default()
+//!     }),
+//!     ..Chat::default()
+//! };
+//!
+//! assert_eq!(
+//!     serde_json::to_value(&chat).unwrap(),
+//!     json!({
+//!         "messages": [],
+//!         "model": "deepseek/deepseek-r1",
+//!         "stream": false,
+//!         "provider": {"order": ["DeepSeek", "Together"], "data_collection": "deny"},
+//!         "models": ["deepseek/deepseek-chat"],
+//!         "reasoning": {"effort": "high"}
+//!     })
+//! );
+//! ```
+
+use serde::Serialize;
+
+use super::Model;
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct OpenRouter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<Provider>,
+    /// Models tried in order if the request's model is unavailable or refuses.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub models: Option<Vec<Model>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub route: Option<Route>,
+    /// Prompt transforms such as `middle-out`, an empty list disables the defaults.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transforms: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<Reasoning>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<UsageAccounting>,
+}
+
+/// Which upstream providers may serve the request.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Provider {
+    /// Provider names to try first, in order, e.g. `DeepSeek`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<Vec<String>>,
+    /// Whether providers outside `order` may be used as backups. Defaults to `true`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_fallbacks: Option<bool>,
+    /// Only use providers supporting every parameter of the request.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub require_parameters: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_collection: Option<DataCollection>,
+}
+
+/// Whether providers that store or train on prompts may be used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DataCollection {
+    Allow,
+    Deny,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Route {
+    /// Tries [`OpenRouter::models`] in order.
+    Fallback,
+}
+
+/// Reasoning token budget, given either as `effort` or as `max_tokens`.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct Reasoning {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effort: Option<Effort>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+    /// Reason without returning the reasoning.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclude: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Effort {
+    Low,
+    Medium,
+    High,
+}
+
+/// Adds cost and detailed token counts to the response's usage.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct UsageAccounting {
+    pub include: bool,
+}
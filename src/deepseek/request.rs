@@ -1,52 +1,300 @@
 //! Slow and complicated serialization.
 //!
 //! A mix of both values being checked for being in range and still enabling
-//! invalid states to be represented e.g. logprobs: false and top_logbrobs: Some(..).
+//! invalid states to be represented e.g. temperature and top_p both being set.
+//! [`Chat::builder`] rules these out at compile time, see [`builder`].
 //!
 //! Fortunately, we aren't [redacted] enough to be unaware of this -> this package will be rewritten, once we are sure the API works how we want it to work.
 
 use serde::Serialize;
 use thiserror::Error;
 
+use super::completion::{ResponseMessage, ToolCall};
+use super::openrouter::OpenRouter;
+use super::schema::Schema;
+use super::Model;
+
+pub mod builder;
+
+pub use builder::ChatBuilder;
+
 #[derive(Debug, Clone, Serialize)]
 pub struct Chat {
     pub messages: Vec<Message>,
-    pub model: String,
-    // #[serde(skip_serializing_if = "Option::is_none")]
-    // pub frequency_penalty: Option<FrequencyPenalty>,
+    /// Falls back to [`Config::model`](super::Config::model) when `None`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<Model>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<FrequencyPenalty>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_tokens: Option<MaxTokens>,
-    // #[serde(skip_serializing_if = "Option::is_none")]
-    // pub presence_penalty: Option<PresencePenalty>,
-    // #[serde(skip_serializing_if = "Option::is_none")]
-    // pub response_format: Option<ResponseFormat>,
-    // #[serde(skip_serializing_if = "Option::is_none")]
-    // pub stop: Option<Stop>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<PresencePenalty>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<ResponseFormat>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Stop>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stream: Option<bool>,
-    // #[serde(skip_serializing_if = "Option::is_none")]
-    // pub stream_options: Option<StreamOptions>,
-    // #[serde(skip_serializing_if = "Option::is_none")]
-    // pub temperature: Option<Temperature>,
-    // #[serde(skip_serializing_if = "Option::is_none")]
-    // pub top_p: Option<TopP>,
-    // #[serde(skip_serializing_if = "Option::is_none")]
-    // pub tools: Option<Vec<Tool>>,
-    // #[serde(skip_serializing_if = "Option::is_none")]
-    // pub tool_choice: Option<ToolChoice>,
-    // #[serde(skip_serializing_if = "Option::is_none")]
-    // pub logprobs: Option<bool>,
-    // #[serde(skip_serializing_if = "Option::is_none")]
-    // pub top_logprobs: Option<TopLogProbs>,
+    /// Only sent for streamed requests.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_options: Option<StreamOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<Temperature>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<TopP>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<Tool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<ToolChoice>,
+    /// Sent as the `logprobs` and `top_logprobs` fields.
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub logprobs: Option<LogProbs>,
+    /// Only sent if [`Config::openrouter`](super::Config::openrouter) is set.
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub openrouter: Option<OpenRouter>,
 }
 
-impl Chat {
-    pub fn default() -> Self {
+impl Default for Chat {
+    fn default() -> Self {
         Self {
             messages: Vec::new(),
-            model: String::from("deepseek/deepseek-r1-distill-llama-70b"),
+            model: None,
+            frequency_penalty: None,
             max_tokens: None,
+            presence_penalty: None,
+            response_format: None,
+            stop: None,
             stream: Some(false),
+            stream_options: None,
+            temperature: None,
+            top_p: None,
+            tools: None,
+            tool_choice: None,
+            logprobs: None,
+            openrouter: None,
+        }
+    }
+}
+
+impl Chat {
+    /// Whether a system or user message mentions JSON, as JSON mode requires.
+    pub fn mentions_json(&self) -> bool {
+        self.messages.iter().any(|message| match message {
+            Message::System { content, .. } | Message::User { content, .. } => {
+                content.to_lowercase().contains("json")
+            }
+            _ => false,
+        })
+    }
+
+    /// Appends an assistant message the model continues from instead of
+    /// answering from scratch, e.g. an opening code fence.
+    ///
+    /// Prefix completion is a DeepSeek beta, the client sends such requests to
+    /// the beta endpoint.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rgi::deepseek::request::{Chat, Message};
+    /// let chat = Chat {
+    ///     messages: vec![Message::User {
+    ///         content: String::from("Write quick sort in Python."),
+    ///         name: None,
+    ///     }],
+    ///     ..Chat::default()
+    /// }
+    /// .prefix("```python\n");
+    ///
+    /// assert!(chat.is_prefix_completion());
+    /// assert!(chat.validate_prefix().is_ok());
+    /// ```
+    pub fn prefix(mut self, content: impl Into<String>) -> Self {
+        self.messages.push(Message::Assistant {
+            content: Some(content.into()),
+            name: None,
+            tool_calls: None,
+            prefix: Some(true),
+            reasoning_content: None,
+        });
+        self
+    }
+
+    /// Whether the final message is an assistant prefix.
+    pub fn is_prefix_completion(&self) -> bool {
+        matches!(self.messages.last(), Some(message) if message.is_prefix())
+    }
+
+    /// # Errors
+    /// Returns `PrefixError::NotLast` if a message other than the final one is a prefix
+    pub fn validate_prefix(&self) -> Result<(), PrefixError> {
+        let Some((_, history)) = self.messages.split_last() else {
+            return Ok(());
+        };
+
+        match history.iter().any(Message::is_prefix) {
+            true => Err(PrefixError::NotLast),
+            false => Ok(()),
+        }
+    }
+
+    /// Rejects `reasoning_content` sent back to the API, which DeepSeek answers
+    /// with a 400. Only a final prefix message may carry it, to continue the
+    /// reasoning of `deepseek-reasoner`.
+    ///
+    /// # Errors
+    /// Returns `ReasoningContentError::Echoed` with the index of the first
+    /// offending message
+    ///
+    /// # Examples
+    /// ```
+    /// # use rgi::deepseek::request::{Chat, Message, ReasoningContentError};
+    /// let answer = Message::Assistant {
+    ///     content: Some(String::from("9.8")),
+    ///     name: None,
+    ///     tool_calls: None,
+    ///     prefix: None,
+    ///     reasoning_content: Some(String::from("Compare the tenths...")),
+    /// };
+    /// let chat = Chat {
+    ///     messages: vec![answer],
+    ///     ..Chat::default()
+    /// };
+    ///
+    /// assert!(matches!(
+    ///     chat.validate_reasoning_content(),
+    ///     Err(ReasoningContentError::Echoed(0))
+    /// ));
+    /// ```
+    pub fn validate_reasoning_content(&self) -> Result<(), ReasoningContentError> {
+        let last = self.messages.len().saturating_sub(1);
+
+        let echoed = self.messages.iter().enumerate().find(|(index, message)| {
+            let Message::Assistant {
+                reasoning_content: Some(_),
+                ..
+            } = message
+            else {
+                return false;
+            };
+
+            *index != last || !message.is_prefix()
+        });
+
+        match echoed {
+            Some((index, _)) => Err(ReasoningContentError::Echoed(index)),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks for parameters `deepseek-reasoner` doesn't support. Other models,
+    /// including DeepSeek's R1 models on OpenRouter, pass unchecked.
+    ///
+    /// # Errors
+    /// Returns the first `ReasonerError` found
+    ///
+    /// # Examples
+    /// ```
+    /// # use rgi::deepseek::request::{Chat, ReasonerError, Temperature};
+    /// # use rgi::deepseek::Model;
+    /// let mut chat = Chat {
+    ///     model: Some(Model::Reasoner),
+    ///     temperature: Some(Temperature::CODING),
+    ///     ..Chat::default()
+    /// };
+    ///
+    /// let error = chat.validate_reasoner().unwrap_err();
+    /// assert!(matches!(error, ReasonerError::Temperature(Model::Reasoner)));
+    /// assert_eq!(error.to_string(), "deepseek-reasoner does not support temperature");
+    ///
+    /// chat.model = Some(Model::OpenRouterR1);
+    /// assert!(chat.validate_reasoner().is_ok());
+    /// ```
+    pub fn validate_reasoner(&self) -> Result<(), ReasonerError> {
+        let model = match &self.model {
+            Some(model @ Model::Reasoner) => model,
+            _ => return Ok(()),
+        };
+
+        if self.temperature.is_some() {
+            Err(ReasonerError::Temperature(model.clone()))
+        } else if self.top_p.is_some() {
+            Err(ReasonerError::TopP(model.clone()))
+        } else if self.logprobs.is_some() {
+            Err(ReasonerError::LogProbs(model.clone()))
+        } else if self.tools.is_some() || self.tool_choice.is_some() {
+            Err(ReasonerError::Tools(model.clone()))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[derive(Error, Debug, Clone, Copy)]
+pub enum PrefixError {
+    #[error("only the final message may be an assistant prefix")]
+    NotLast,
+}
+
+#[derive(Error, Debug, Clone, Copy)]
+pub enum ReasoningContentError {
+    #[error("message {0} echoes reasoning_content, which only a final prefix may carry")]
+    Echoed(usize),
+}
+
+#[derive(Error, Debug, Clone)]
+pub enum ReasonerError {
+    #[error("{0} does not support temperature")]
+    Temperature(Model),
+    #[error("{0} does not support top_p")]
+    TopP(Model),
+    #[error("{0} does not support logprobs")]
+    LogProbs(Model),
+    #[error("{0} does not support tools")]
+    Tools(Model),
+}
+
+impl Message {
+    pub fn is_prefix(&self) -> bool {
+        matches!(
+            self,
+            Message::Assistant {
+                prefix: Some(true),
+                ..
+            }
+        )
+    }
+}
+
+/// Turns an answer into the assistant message that continues the conversation.
+///
+/// `reasoning_content` is dropped: DeepSeek rejects requests echoing it, keep it
+/// in a [`Conversation`](super::conversation::Conversation) to display it.
+///
+/// # Examples
+/// ```
+/// # use rgi::deepseek::completion::ResponseMessage;
+/// # use rgi::deepseek::request::Message;
+/// let answer: ResponseMessage = serde_json::from_str(
+///     r#"{"role":"assistant","content":"9.11 < 9.8","reasoning_content":"Compare decimals..."}"#,
+/// )
+/// .unwrap();
+///
+/// assert_eq!(
+///     serde_json::to_string(&Message::from(answer)).unwrap(),
+///     r#"{"role":"assistant","content":"9.11 < 9.8"}"#
+/// );
+/// ```
+impl From<ResponseMessage> for Message {
+    fn from(message: ResponseMessage) -> Self {
+        Message::Assistant {
+            content: message.content,
+            name: None,
+            tool_calls: message
+                .tool_calls
+                .filter(|tool_calls| !tool_calls.is_empty()),
+            prefix: None,
+            reasoning_content: None,
         }
     }
 }
@@ -110,71 +358,97 @@ pub enum MaxTokenError {
     TooHigh,
 }
 
-// #[derive(Debug, Serialize, Clone)]
-// #[serde(tag = "type", content = "function", rename_all = "snake_case")]
-// pub enum Tool {
-//     Function {
-//         description: String,
-//         name: String,
-//         parameters: FunctionParameters,
-//     },
-// }
-
-// #[derive(Debug, Serialize, Clone)]
-// #[serde(tag = "type", rename_all = "snake_case")]
-// pub enum FunctionParameters {
-//     Object {
-//         properties: HashMap<String, Parameter>,
-//         required: Vec<String>,
-//     },
-// }
-
-// #[derive(Debug, Serialize, Clone)]
-// pub struct Parameter {
-//     #[serde(rename = "type")]
-//     pub type_: String,
-//     pub description: String,
-// }
-
-// #[derive(Debug, Clone)]
-// pub enum ToolChoice {
-//     None,
-//     Auto,
-//     Required,
-//     Function(String),
-// }
-
-// impl serde::Serialize for ToolChoice {
-//     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-//     where
-//         S: serde::Serializer,
-//     {
-//         match self {
-//             ToolChoice::None => serializer.serialize_str("none"),
-//             ToolChoice::Auto => serializer.serialize_str("auto"),
-//             ToolChoice::Required => serializer.serialize_str("required"),
-//             ToolChoice::Function(name) => {
-//                 #[derive(serde::Serialize)]
-//                 struct ToolCall<'a> {
-//                     #[serde(rename = "type")]
-//                     type_: &'static str,
-//                     function: FunctionName<'a>,
-//                 }
-
-//                 #[derive(serde::Serialize)]
-//                 struct FunctionName<'a> {
-//                     name: &'a str,
-//                 }
-
-//                 ToolCall {
-//                     type_: "function",
-//                     function: FunctionName { name },
-//                 }
-//                 .serialize(serializer)
-//             }
-//         }
-//     }
-// }
+/// `max_tokens` of a [`FimCompletion`], which DeepSeek caps at 4K tokens.
+///
+/// # Examples
+/// ```
+/// # use rgi::deepseek::request::{FimMaxTokenError, FimMaxTokens};
+/// assert_eq!(FimMaxTokens::new(4096).unwrap().get(), 4096);
+///
+/// assert!(matches!(
+///     FimMaxTokens::new(4097),
+///     Err(FimMaxTokenError::TooHigh)
+/// ));
+/// ```
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(transparent)]
+pub struct FimMaxTokens(u16);
+
+impl FimMaxTokens {
+    pub const MIN: u16 = 1;
+    pub const MAX: u16 = 4096;
+
+    pub const fn new(value: u16) -> Result<Self, FimMaxTokenError> {
+        match value {
+            _ if value < FimMaxTokens::MIN => Err(FimMaxTokenError::TooLow),
+            _ if value > FimMaxTokens::MAX => Err(FimMaxTokenError::TooHigh),
+            _ => Ok(Self(value)),
+        }
+    }
+
+    pub const fn get(&self) -> u16 {
+        self.0
+    }
+}
+
+#[derive(Error, Debug, Clone, Copy)]
+pub enum FimMaxTokenError {
+    #[error("max_tokens < {min} (FimMaxTokens::MIN)", min = FimMaxTokens::MIN)]
+    TooLow,
+    #[error("max_tokens > {max} (FimMaxTokens::MAX)", max = FimMaxTokens::MAX)]
+    TooHigh,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(tag = "type", content = "function", rename_all = "snake_case")]
+pub enum Tool {
+    Function {
+        description: String,
+        name: String,
+        /// JSON Schema of the arguments, see [`Schema`].
+        parameters: Schema,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub enum ToolChoice {
+    None,
+    Auto,
+    Required,
+    Function(String),
+}
+
+impl serde::Serialize for ToolChoice {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            ToolChoice::None => serializer.serialize_str("none"),
+            ToolChoice::Auto => serializer.serialize_str("auto"),
+            ToolChoice::Required => serializer.serialize_str("required"),
+            ToolChoice::Function(name) => {
+                #[derive(serde::Serialize)]
+                struct ToolCall<'a> {
+                    #[serde(rename = "type")]
+                    type_: &'static str,
+                    function: FunctionName<'a>,
+                }
+
+                #[derive(serde::Serialize)]
+                struct FunctionName<'a> {
+                    name: &'a str,
+                }
+
+                ToolCall {
+                    type_: "function",
+                    function: FunctionName { name },
+                }
+                .serialize(serializer)
+            }
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(tag = "role", rename_all = "snake_case")]
@@ -190,10 +464,19 @@ pub enum Message {
         name: Option<String>,
     },
     Assistant {
-        // Docs are stating this as 'nullable required'. For now content is required.
-        content: String,
+        // Docs are stating this as 'nullable required', it is null when only tools are called.
+        content: Option<String>,
         #[serde(skip_serializing_if = "Option::is_none")]
         name: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        tool_calls: Option<Vec<ToolCall>>,
+        /// Marks the final message as the start of the answer for the model to
+        /// continue, see [`Chat::prefix`].
+        #[serde(skip_serializing_if = "Option::is_none")]
+        prefix: Option<bool>,
+        /// Reasoning the answer continues from, only read on a prefix message.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        reasoning_content: Option<String>,
     },
     Tool {
         content: String,
@@ -201,391 +484,556 @@ pub enum Message {
     },
 }
 
-// /// Positive values penalize new tokens based on their existing frequency in the text so far, decreasing the model's likelihood to repeat the same line verbatim.
-// ///
-// /// Number between -2.0 and 2.0.
-// ///
-// /// # Examples
-// /// ```
-// /// # use rgi::deepseek::primitives::{FrequencyPenalty, FrequencyPenaltyError};
-// /// // Valid value
-// /// let penalty = FrequencyPenalty::new(1.5).unwrap();
-// /// assert_eq!(penalty.get(), 1.5);
-// ///
-// /// // Value too high
-// /// assert!(matches!(
-// ///     FrequencyPenalty::new(3.0),
-// ///     Err(FrequencyPenaltyError::TooHigh)
-// /// ));
-// ///
-// /// // Value too low
-// /// assert!(matches!(
-// ///     FrequencyPenalty::new(-3.0),
-// ///     Err(FrequencyPenaltyError::TooLow)
-// /// ));
-// /// ```
-// #[derive(Debug, Clone, Copy, Serialize)]
-// #[serde(transparent)]
-// pub struct FrequencyPenalty(f32);
-
-// #[derive(Error, Debug, Clone, Copy)]
-// pub enum FrequencyPenaltyError {
-//     #[error("frequency_penalty < {min} (FrequencyPenalty::MIN)", min = FrequencyPenalty::MIN)]
-//     TooLow,
-//     #[error("frequency_penalty > {max} (FrequencyPenalty::MAX)", max = FrequencyPenalty::MAX)]
-//     TooHigh,
-// }
-
-// impl FrequencyPenalty {
-//     pub const MIN: f32 = -2.0;
-//     pub const MAX: f32 = 2.0;
-//     pub const DEFAULT: f32 = 0.0;
-
-//     /// Creates a new FrequencyPenalty.
-//     ///
-//     /// # Errors
-//     /// Returns `FrequencyPenaltyError::TooLow` if value is less than -2.0
-//     /// Returns `FrequencyPenaltyError::TooHigh` if value is greater than 2.0
-//     pub const fn new(value: f32) -> Result<Self, FrequencyPenaltyError> {
-//         match value {
-//             _ if value < FrequencyPenalty::MIN => Err(FrequencyPenaltyError::TooLow),
-//             _ if value > FrequencyPenalty::MAX => Err(FrequencyPenaltyError::TooHigh),
-//             _ => Ok(Self(value)),
-//         }
-//     }
-
-//     /// Returns the inner f32 value
-//     pub const fn get(&self) -> f32 {
-//         self.0
-//     }
-// }
-
-// impl Default for FrequencyPenalty {
-//     fn default() -> Self {
-//         Self(Self::DEFAULT)
-//     }
-// }
-
-// impl TryFrom<f32> for FrequencyPenalty {
-//     type Error = FrequencyPenaltyError;
-
-//     /// Attempts to create FrequencyPenalty from f32 value
-//     fn try_from(value: f32) -> Result<Self, Self::Error> {
-//         Self::new(value)
-//     }
-// }
-
-// /// Positive values penalize new tokens based on whether they appear in the text so far, increasing the model's likelihood to talk about new topics.
-// ///
-// /// Number between -2.0 and 2.0.
-// ///
-// /// # Examples
-// /// ```
-// /// # use rgi::deepseek::primitives::{PresencePenalty, PresencePenaltyError};
-// /// // Valid value creation
-// /// let penalty = PresencePenalty::new(1.5).unwrap();
-// /// assert_eq!(penalty.get(), 1.5);
-// ///
-// /// // Test boundary violations
-// /// assert!(matches!(
-// ///     PresencePenalty::new(3.0),
-// ///     Err(PresencePenaltyError::TooHigh)
-// /// ));
-// ///
-// /// assert!(matches!(
-// ///     PresencePenalty::new(-3.0),
-// ///     Err(PresencePenaltyError::TooLow)
-// /// ));
-// /// ```
-// #[derive(Debug, Clone, Copy, Serialize)]
-// #[serde(transparent)]
-// pub struct PresencePenalty(f32);
-
-// #[derive(Error, Debug, Clone, Copy)]
-// pub enum PresencePenaltyError {
-//     #[error("presence_penalty < {min} (PresencePenalty::MIN)", min = PresencePenalty::MIN)]
-//     TooLow,
-//     #[error("presence_penalty > {max} (PresencePenalty::MAX)", max = PresencePenalty::MAX)]
-//     TooHigh,
-// }
-
-// impl PresencePenalty {
-//     pub const MIN: f32 = -2.0;
-//     pub const MAX: f32 = 2.0;
-//     pub const DEFAULT: f32 = 0.0;
-
-//     /// Creates a new PresencePenalty.
-//     ///
-//     /// # Errors
-//     /// Returns `PresencePenaltyError::TooLow` if value is less than -2.0
-//     /// Returns `PresencePenaltyError::TooHigh` if value is greater than 2.0
-//     pub const fn new(value: f32) -> Result<Self, PresencePenaltyError> {
-//         match value {
-//             _ if value < PresencePenalty::MIN => Err(PresencePenaltyError::TooLow),
-//             _ if value > PresencePenalty::MAX => Err(PresencePenaltyError::TooHigh),
-//             _ => Ok(Self(value)),
-//         }
-//     }
-
-//     /// Returns the inner f32 value
-//     pub const fn get(&self) -> f32 {
-//         self.0
-//     }
-// }
-
-// impl Default for PresencePenalty {
-//     fn default() -> Self {
-//         Self(Self::DEFAULT)
-//     }
-// }
-
-// impl TryFrom<f32> for PresencePenalty {
-//     type Error = PresencePenaltyError;
-
-//     /// Attempts to create PresencePenalty from f32 value
-//     fn try_from(value: f32) -> Result<Self, Self::Error> {
-//         Self::new(value)
-//     }
-// }
-
-// #[derive(Debug, Clone, Serialize, Default)]
-// #[serde(rename_all = "snake_case")]
-// pub enum ResponseFormat {
-//     #[default]
-//     Text,
-//     JsonObject,
-// }
-
-// #[derive(Debug, Clone, Default, Serialize)]
-// #[serde(transparent)]
-// pub struct Stop(Vec<String>);
-
-// impl Stop {
-//     pub const MAX_LEN: usize = 16;
-
-// pub fn new(stop: Vec<String>) -> Result<Self, StopError> {
-//     Some(stop)
-//         .filter(|stop| stop.len() <= Self::MAX_LEN)
-//         .map(Self)
-//         .ok_or(StopError::TooManyStops)
-// }
-
-//     pub fn get(&self) -> &[String] {
-//         &self.0
-//     }
-// }
-
-// #[derive(Error, Debug, Clone, Copy)]
-// pub enum StopError {
-//     #[error("stop.len() > {max} (Stop::MAX_LEN)", max = Stop::MAX_LEN)]
-//     TooManyStops,
-// }
-
-// #[derive(Debug, Clone, Serialize)]
-// pub struct StreamOptions {
-//     pub include_usage: Option<bool>,
-// }
-
-// /// Higher values will make the output more random, while lower values will make it more focused and deterministic.
-// ///
-// /// Number between 0.0 and 2.0.
-// /// ! Alter this or `top_p` but not both !
-// ///
-// /// # Examples
-// /// ```
-// /// # use rgi::deepseek::primitives::{Temperature, TemperatureError};
-// /// // Valid value creation
-// /// let temp = Temperature::new(0.7).unwrap();
-// /// assert_eq!(temp.get(), 0.7);
-// ///
-// /// // Boundary violations
-// /// assert!(matches!(
-// ///     Temperature::new(3.0),
-// ///     Err(TemperatureError::TooHigh)
-// /// ));
-// ///
-// /// assert!(matches!(
-// ///     Temperature::new(-1.0),
-// ///     Err(TemperatureError::TooLow)
-// /// ));
-// ///
-// /// // Preset temperature checks
-// /// assert_eq!(Temperature::CODING.get(), 0.0);
-// /// assert_eq!(Temperature::DATA.get(), 1.0);
-// /// assert_eq!(Temperature::CONVERSATION.get(), 1.3);
-// /// assert_eq!(Temperature::TRANSLATION.get(), 1.3);
-// /// assert_eq!(Temperature::POETRY.get(), 2.0);
-// /// ```
-// #[derive(Debug, Clone, Copy, Serialize)]
-// #[serde(transparent)]
-// pub struct Temperature(f32);
-
-// #[derive(Error, Debug, Clone, Copy)]
-// pub enum TemperatureError {
-//     #[error("temperature < {min} (Temperature::MIN)", min = Temperature::MIN)]
-//     TooLow,
-//     #[error("temperature > {max} (Temperature::MAX)", max = Temperature::MAX)]
-//     TooHigh,
-// }
-
-// impl Temperature {
-//     pub const MIN: f32 = 0.0;
-//     pub const MAX: f32 = 2.0;
-//     pub const DEFAULT: f32 = 1.0;
-
-//     pub const CODING: Self = Self(Self::MIN);
-//     pub const DATA: Self = Self(1.0);
-//     pub const CONVERSATION: Self = Self(1.3);
-//     pub const TRANSLATION: Self = Self(1.3);
-//     pub const POETRY: Self = Self(Self::MAX);
-
-//     /// Creates a new Temperature.
-//     ///
-//     /// # Errors
-//     /// Returns `TemperatureError::TooLow` if value is less than 0.0
-//     /// Returns `TemperatureError::TooHigh` if value is greater than 2.0
-//     pub const fn new(temperature: f32) -> Result<Self, TemperatureError> {
-//         match temperature {
-//             _ if temperature < Temperature::MIN => Err(TemperatureError::TooLow),
-//             _ if temperature > Temperature::MAX => Err(TemperatureError::TooHigh),
-//             _ => Ok(Self(temperature)),
-//         }
-//     }
-
-//     /// Returns the inner f32 value
-//     pub const fn get(&self) -> f32 {
-//         self.0
-//     }
-// }
-
-// impl Default for Temperature {
-//     fn default() -> Self {
-//         Self(Self::DEFAULT)
-//     }
-// }
-
-// impl TryFrom<f32> for Temperature {
-//     type Error = TemperatureError;
-
-//     fn try_from(temperature: f32) -> Result<Self, Self::Error> {
-//         Self::new(temperature)
-//     }
-// }
-
-// #[derive(Debug, Clone, Copy, Serialize)]
-// pub struct TopP(f32);
-
-// #[derive(Error, Debug, Clone, Copy)]
-// pub enum TopPError {
-//     #[error("top_p < {min} (TopP::MIN)", min = TopP::MIN)]
-//     TooLow,
-//     #[error("top_p > {max} (TopP::MAX)", max = TopP::MAX)]
-//     TooHigh,
-// }
-
-// impl TopP {
-//     pub const MIN: f32 = 0.0;
-//     pub const MAX: f32 = 1.0;
-//     pub const DEFAULT: f32 = 1.0;
-
-//     /// Creates a new TopP.
-//     ///
-//     /// # Errors
-//     /// Returns `TopPError::TooLow` if value is less than 0.0
-//     /// Returns `TopPError::TooHigh` if value is greater than 1.0
-//     pub const fn new(top_p: f32) -> Result<Self, TopPError> {
-//         match top_p {
-//             _ if top_p < TopP::MIN => Err(TopPError::TooLow),
-//             _ if top_p > TopP::MAX => Err(TopPError::TooHigh),
-//             _ => Ok(Self(top_p)),
-//         }
-//     }
-
-//     /// Returns the inner f32 value
-//     pub const fn get(&self) -> f32 {
-//         self.0
-//     }
-// }
-
-// impl Default for TopP {
-//     fn default() -> Self {
-//         Self(Self::DEFAULT)
-//     }
-// }
-
-// impl TryFrom<f32> for TopP {
-//     type Error = TopPError;
-
-//     fn try_from(top_p: f32) -> Result<Self, Self::Error> {
-//         Self::new(top_p)
-//     }
-// }
-
-// /// Number of most likely tokens to return at each token position, each with an associated log probability.
-// ///
-// /// ! `logprobs` must be `true` to use this parameter !
-// ///
-// /// Number between 0 and 20.
-// ///
-// /// # Examples
-// /// ```
-// /// # use rgi::deepseek::primitives::{TopLogProbs, TopLogProbsError};
-// /// // Valid value creation
-// /// let probs = TopLogProbs::new(5).unwrap();
-// /// assert_eq!(probs.get(), 5);
-// ///
-// /// // Test boundary values
-// /// let min_probs = TopLogProbs::new(0).unwrap();
-// /// assert_eq!(min_probs.get(), 0);
-// /// let max_probs = TopLogProbs::new(20).unwrap();
-// /// assert_eq!(max_probs.get(), 20);
-// ///
-// /// // Test error conditions
-// /// assert!(matches!(TopLogProbs::new(21), Err(TopLogProbsError::TooHigh)));
-// /// assert!(matches!(TopLogProbs::new(-1), Err(TopLogProbsError::TooLow)));
-// /// ```
-// #[derive(Debug, Clone, Copy, Serialize)]
-// #[serde(transparent)]
-// pub struct TopLogProbs(i32);
-
-// #[derive(Error, Debug, Clone, Copy)]
-// pub enum TopLogProbsError {
-//     #[error("top_logprobs < {min} (TopLogProbs::MIN)", min = TopLogProbs::MIN)]
-//     TooLow,
-//     #[error("top_logprobs > {max} (TopLogProbs::MAX)", max = TopLogProbs::MAX)]
-//     TooHigh,
-// }
-
-// impl TopLogProbs {
-//     pub const MIN: i32 = 0;
-//     pub const MAX: i32 = 20;
-
-//     /// Creates a new TopLogProbs.
-//     ///
-//     /// # Errors
-//     /// Returns `TopLogProbsError::TooLow` if value is less than 0
-//     /// Returns `TopLogProbsError::TooHigh` if value is greater than 20
-//     pub const fn new(top_logprobs: i32) -> Result<Self, TopLogProbsError> {
-//         match top_logprobs {
-//             _ if top_logprobs < TopLogProbs::MIN => Err(TopLogProbsError::TooLow),
-//             _ if top_logprobs > TopLogProbs::MAX => Err(TopLogProbsError::TooHigh),
-//             _ => Ok(Self(top_logprobs)),
-//         }
-//     }
-
-//     /// Returns the inner i32 value
-//     pub const fn get(&self) -> i32 {
-//         self.0
-//     }
-// }
-
-// impl TryFrom<i32> for TopLogProbs {
-//     type Error = TopLogProbsError;
-
-//     fn try_from(top_logprobs: i32) -> Result<Self, Self::Error> {
-//         Self::new(top_logprobs)
-//     }
-// }
-
-// // TODO
-// pub struct FIMCompletion {}
+/// Positive values penalize new tokens based on their existing frequency in the text so far, decreasing the model's likelihood to repeat the same line verbatim.
+///
+/// Number between -2.0 and 2.0.
+///
+/// # Examples
+/// ```
+/// # use rgi::deepseek::request::{FrequencyPenalty, FrequencyPenaltyError};
+/// // Valid value
+/// let penalty = FrequencyPenalty::new(1.5).unwrap();
+/// assert_eq!(penalty.get(), 1.5);
+///
+/// // Value too high
+/// assert!(matches!(
+///     FrequencyPenalty::new(3.0),
+///     Err(FrequencyPenaltyError::TooHigh)
+/// ));
+///
+/// // Value too low
+/// assert!(matches!(
+///     FrequencyPenalty::new(-3.0),
+///     Err(FrequencyPenaltyError::TooLow)
+/// ));
+/// ```
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(transparent)]
+pub struct FrequencyPenalty(f32);
+
+#[derive(Error, Debug, Clone, Copy)]
+pub enum FrequencyPenaltyError {
+    #[error("frequency_penalty < {min} (FrequencyPenalty::MIN)", min = FrequencyPenalty::MIN)]
+    TooLow,
+    #[error("frequency_penalty > {max} (FrequencyPenalty::MAX)", max = FrequencyPenalty::MAX)]
+    TooHigh,
+}
+
+impl FrequencyPenalty {
+    pub const MIN: f32 = -2.0;
+    pub const MAX: f32 = 2.0;
+    pub const DEFAULT: f32 = 0.0;
+
+    /// Creates a new FrequencyPenalty.
+    ///
+    /// # Errors
+    /// Returns `FrequencyPenaltyError::TooLow` if value is less than -2.0
+    /// Returns `FrequencyPenaltyError::TooHigh` if value is greater than 2.0
+    pub const fn new(value: f32) -> Result<Self, FrequencyPenaltyError> {
+        match value {
+            _ if value < FrequencyPenalty::MIN => Err(FrequencyPenaltyError::TooLow),
+            _ if value > FrequencyPenalty::MAX => Err(FrequencyPenaltyError::TooHigh),
+            _ => Ok(Self(value)),
+        }
+    }
+
+    /// Returns the inner f32 value
+    pub const fn get(&self) -> f32 {
+        self.0
+    }
+}
+
+impl Default for FrequencyPenalty {
+    fn default() -> Self {
+        Self(Self::DEFAULT)
+    }
+}
+
+impl TryFrom<f32> for FrequencyPenalty {
+    type Error = FrequencyPenaltyError;
+
+    /// Attempts to create FrequencyPenalty from f32 value
+    fn try_from(value: f32) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+/// Positive values penalize new tokens based on whether they appear in the text so far, increasing the model's likelihood to talk about new topics.
+///
+/// Number between -2.0 and 2.0.
+///
+/// # Examples
+/// ```
+/// # use rgi::deepseek::request::{PresencePenalty, PresencePenaltyError};
+/// // Valid value creation
+/// let penalty = PresencePenalty::new(1.5).unwrap();
+/// assert_eq!(penalty.get(), 1.5);
+///
+/// // Test boundary violations
+/// assert!(matches!(
+///     PresencePenalty::new(3.0),
+///     Err(PresencePenaltyError::TooHigh)
+/// ));
+///
+/// assert!(matches!(
+///     PresencePenalty::new(-3.0),
+///     Err(PresencePenaltyError::TooLow)
+/// ));
+/// ```
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(transparent)]
+pub struct PresencePenalty(f32);
+
+#[derive(Error, Debug, Clone, Copy)]
+pub enum PresencePenaltyError {
+    #[error("presence_penalty < {min} (PresencePenalty::MIN)", min = PresencePenalty::MIN)]
+    TooLow,
+    #[error("presence_penalty > {max} (PresencePenalty::MAX)", max = PresencePenalty::MAX)]
+    TooHigh,
+}
+
+impl PresencePenalty {
+    pub const MIN: f32 = -2.0;
+    pub const MAX: f32 = 2.0;
+    pub const DEFAULT: f32 = 0.0;
+
+    /// Creates a new PresencePenalty.
+    ///
+    /// # Errors
+    /// Returns `PresencePenaltyError::TooLow` if value is less than -2.0
+    /// Returns `PresencePenaltyError::TooHigh` if value is greater than 2.0
+    pub const fn new(value: f32) -> Result<Self, PresencePenaltyError> {
+        match value {
+            _ if value < PresencePenalty::MIN => Err(PresencePenaltyError::TooLow),
+            _ if value > PresencePenalty::MAX => Err(PresencePenaltyError::TooHigh),
+            _ => Ok(Self(value)),
+        }
+    }
+
+    /// Returns the inner f32 value
+    pub const fn get(&self) -> f32 {
+        self.0
+    }
+}
+
+impl Default for PresencePenalty {
+    fn default() -> Self {
+        Self(Self::DEFAULT)
+    }
+}
+
+impl TryFrom<f32> for PresencePenalty {
+    type Error = PresencePenaltyError;
+
+    /// Attempts to create PresencePenalty from f32 value
+    fn try_from(value: f32) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+/// Format the model must answer in.
+///
+/// `JsonObject` enables JSON mode, which requires the system or user prompt to
+/// mention JSON and should include an example of the expected output.
+///
+/// # Examples
+/// ```
+/// # use rgi::deepseek::request::ResponseFormat;
+/// assert_eq!(
+///     serde_json::to_string(&ResponseFormat::JsonObject).unwrap(),
+///     r#"{"type":"json_object"}"#
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResponseFormat {
+    #[default]
+    Text,
+    JsonObject,
+}
+
+#[derive(Error, Debug, Clone, Copy)]
+pub enum ResponseFormatError {
+    #[error("JSON mode requires the word \"json\" in a system or user message")]
+    MissingJsonPrompt,
+}
+
+/// Up to 16 sequences where the API will stop generating further tokens.
+///
+/// # Examples
+/// ```
+/// # use rgi::deepseek::request::{Stop, StopError};
+/// let stop = Stop::new(vec![String::from("\n\n"), String::from("END")]).unwrap();
+/// assert_eq!(stop.get().len(), 2);
+///
+/// let too_many = vec![String::from("."); Stop::MAX_LEN + 1];
+/// assert!(matches!(Stop::new(too_many), Err(StopError::TooManyStops)));
+/// ```
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(transparent)]
+pub struct Stop(Vec<String>);
+
+impl Stop {
+    pub const MAX_LEN: usize = 16;
+
+    /// # Errors
+    /// Returns `StopError::TooManyStops` if more than 16 sequences are given
+    pub fn new(stop: Vec<String>) -> Result<Self, StopError> {
+        Some(stop)
+            .filter(|stop| stop.len() <= Self::MAX_LEN)
+            .map(Self)
+            .ok_or(StopError::TooManyStops)
+    }
+
+    pub fn get(&self) -> &[String] {
+        &self.0
+    }
+}
+
+impl TryFrom<Vec<String>> for Stop {
+    type Error = StopError;
+
+    fn try_from(stop: Vec<String>) -> Result<Self, Self::Error> {
+        Self::new(stop)
+    }
+}
+
+#[derive(Error, Debug, Clone, Copy)]
+pub enum StopError {
+    #[error("stop.len() > {max} (Stop::MAX_LEN)", max = Stop::MAX_LEN)]
+    TooManyStops,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct StreamOptions {
+    /// Sends an additional chunk before `[DONE]` carrying the usage of the whole
+    /// request and no choices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_usage: Option<bool>,
+}
+
+/// Higher values will make the output more random, while lower values will make it more focused and deterministic.
+///
+/// Number between 0.0 and 2.0.
+/// ! Alter this or `top_p` but not both !
+///
+/// # Examples
+/// ```
+/// # use rgi::deepseek::request::{Temperature, TemperatureError};
+/// // Valid value creation
+/// let temp = Temperature::new(0.7).unwrap();
+/// assert_eq!(temp.get(), 0.7);
+///
+/// // Boundary violations
+/// assert!(matches!(
+///     Temperature::new(3.0),
+///     Err(TemperatureError::TooHigh)
+/// ));
+///
+/// assert!(matches!(
+///     Temperature::new(-1.0),
+///     Err(TemperatureError::TooLow)
+/// ));
+///
+/// // Preset temperature checks
+/// assert_eq!(Temperature::CODING.get(), 0.0);
+/// assert_eq!(Temperature::DATA.get(), 1.0);
+/// assert_eq!(Temperature::CONVERSATION.get(), 1.3);
+/// assert_eq!(Temperature::TRANSLATION.get(), 1.3);
+/// assert_eq!(Temperature::POETRY.get(), 2.0);
+/// ```
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(transparent)]
+pub struct Temperature(f32);
+
+#[derive(Error, Debug, Clone, Copy)]
+pub enum TemperatureError {
+    #[error("temperature < {min} (Temperature::MIN)", min = Temperature::MIN)]
+    TooLow,
+    #[error("temperature > {max} (Temperature::MAX)", max = Temperature::MAX)]
+    TooHigh,
+}
+
+impl Temperature {
+    pub const MIN: f32 = 0.0;
+    pub const MAX: f32 = 2.0;
+    pub const DEFAULT: f32 = 1.0;
+
+    pub const CODING: Self = Self(Self::MIN);
+    pub const DATA: Self = Self(1.0);
+    pub const CONVERSATION: Self = Self(1.3);
+    pub const TRANSLATION: Self = Self(1.3);
+    pub const POETRY: Self = Self(Self::MAX);
+
+    /// Creates a new Temperature.
+    ///
+    /// # Errors
+    /// Returns `TemperatureError::TooLow` if value is less than 0.0
+    /// Returns `TemperatureError::TooHigh` if value is greater than 2.0
+    pub const fn new(temperature: f32) -> Result<Self, TemperatureError> {
+        match temperature {
+            _ if temperature < Temperature::MIN => Err(TemperatureError::TooLow),
+            _ if temperature > Temperature::MAX => Err(TemperatureError::TooHigh),
+            _ => Ok(Self(temperature)),
+        }
+    }
+
+    /// Returns the inner f32 value
+    pub const fn get(&self) -> f32 {
+        self.0
+    }
+}
+
+impl Default for Temperature {
+    fn default() -> Self {
+        Self(Self::DEFAULT)
+    }
+}
+
+impl TryFrom<f32> for Temperature {
+    type Error = TemperatureError;
+
+    fn try_from(temperature: f32) -> Result<Self, Self::Error> {
+        Self::new(temperature)
+    }
+}
+
+/// An alternative to sampling with temperature, called nucleus sampling, where the model
+/// considers the results of the tokens with top_p probability mass.
+///
+/// Number between 0.0 and 1.0.
+/// ! Alter this or `temperature` but not both !
+///
+/// # Examples
+/// ```
+/// # use rgi::deepseek::request::{TopP, TopPError};
+/// // Valid value creation
+/// let top_p = TopP::new(0.1).unwrap();
+/// assert_eq!(top_p.get(), 0.1);
+///
+/// // Boundary violations
+/// assert!(matches!(TopP::new(1.5), Err(TopPError::TooHigh)));
+/// assert!(matches!(TopP::new(-0.1), Err(TopPError::TooLow)));
+/// ```
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(transparent)]
+pub struct TopP(f32);
+
+#[derive(Error, Debug, Clone, Copy)]
+pub enum TopPError {
+    #[error("top_p < {min} (TopP::MIN)", min = TopP::MIN)]
+    TooLow,
+    #[error("top_p > {max} (TopP::MAX)", max = TopP::MAX)]
+    TooHigh,
+}
+
+impl TopP {
+    pub const MIN: f32 = 0.0;
+    pub const MAX: f32 = 1.0;
+    pub const DEFAULT: f32 = 1.0;
+
+    /// Creates a new TopP.
+    ///
+    /// # Errors
+    /// Returns `TopPError::TooLow` if value is less than 0.0
+    /// Returns `TopPError::TooHigh` if value is greater than 1.0
+    pub const fn new(top_p: f32) -> Result<Self, TopPError> {
+        match top_p {
+            _ if top_p < TopP::MIN => Err(TopPError::TooLow),
+            _ if top_p > TopP::MAX => Err(TopPError::TooHigh),
+            _ => Ok(Self(top_p)),
+        }
+    }
+
+    /// Returns the inner f32 value
+    pub const fn get(&self) -> f32 {
+        self.0
+    }
+}
+
+impl Default for TopP {
+    fn default() -> Self {
+        Self(Self::DEFAULT)
+    }
+}
+
+impl TryFrom<f32> for TopP {
+    type Error = TopPError;
+
+    fn try_from(top_p: f32) -> Result<Self, Self::Error> {
+        Self::new(top_p)
+    }
+}
+
+/// Requests the log probability of every output token.
+///
+/// Setting `top_logprobs` requires `logprobs: true`, so both are set together.
+///
+/// # Examples
+/// ```
+/// # use rgi::deepseek::request::{LogProbs, TopLogProbs};
+/// assert_eq!(
+///     serde_json::to_string(&LogProbs::new()).unwrap(),
+///     r#"{"logprobs":true}"#
+/// );
+///
+/// let top = LogProbs::with_top(TopLogProbs::new(5).unwrap());
+/// assert_eq!(
+///     serde_json::to_string(&top).unwrap(),
+///     r#"{"logprobs":true,"top_logprobs":5}"#
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogProbs {
+    top_logprobs: Option<TopLogProbs>,
+}
+
+impl LogProbs {
+    pub const fn new() -> Self {
+        Self { top_logprobs: None }
+    }
+
+    /// Additionally returns the `top_logprobs` most likely tokens at every position.
+    pub const fn with_top(top_logprobs: TopLogProbs) -> Self {
+        Self {
+            top_logprobs: Some(top_logprobs),
+        }
+    }
+
+    pub const fn top_logprobs(&self) -> Option<TopLogProbs> {
+        self.top_logprobs
+    }
+}
+
+impl Serialize for LogProbs {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("logprobs", &true)?;
+        if let Some(top_logprobs) = self.top_logprobs {
+            map.serialize_entry("top_logprobs", &top_logprobs)?;
+        }
+        map.end()
+    }
+}
+
+/// Number of most likely tokens to return at each token position, each with an associated log probability.
+///
+/// Number between 0 and 20.
+///
+/// # Examples
+/// ```
+/// # use rgi::deepseek::request::{TopLogProbs, TopLogProbsError};
+/// // Valid value creation
+/// let probs = TopLogProbs::new(5).unwrap();
+/// assert_eq!(probs.get(), 5);
+///
+/// // Test boundary values
+/// let min_probs = TopLogProbs::new(0).unwrap();
+/// assert_eq!(min_probs.get(), 0);
+/// let max_probs = TopLogProbs::new(20).unwrap();
+/// assert_eq!(max_probs.get(), 20);
+///
+/// // Test error conditions
+/// assert!(matches!(TopLogProbs::new(21), Err(TopLogProbsError::TooHigh)));
+/// assert!(matches!(TopLogProbs::new(-1), Err(TopLogProbsError::TooLow)));
+/// ```
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(transparent)]
+pub struct TopLogProbs(i32);
+
+#[derive(Error, Debug, Clone, Copy)]
+pub enum TopLogProbsError {
+    #[error("top_logprobs < {min} (TopLogProbs::MIN)", min = TopLogProbs::MIN)]
+    TooLow,
+    #[error("top_logprobs > {max} (TopLogProbs::MAX)", max = TopLogProbs::MAX)]
+    TooHigh,
+}
+
+impl TopLogProbs {
+    pub const MIN: i32 = 0;
+    pub const MAX: i32 = 20;
+
+    /// Creates a new TopLogProbs.
+    ///
+    /// # Errors
+    /// Returns `TopLogProbsError::TooLow` if value is less than 0
+    /// Returns `TopLogProbsError::TooHigh` if value is greater than 20
+    pub const fn new(top_logprobs: i32) -> Result<Self, TopLogProbsError> {
+        match top_logprobs {
+            _ if top_logprobs < TopLogProbs::MIN => Err(TopLogProbsError::TooLow),
+            _ if top_logprobs > TopLogProbs::MAX => Err(TopLogProbsError::TooHigh),
+            _ => Ok(Self(top_logprobs)),
+        }
+    }
+
+    /// Returns the inner i32 value
+    pub const fn get(&self) -> i32 {
+        self.0
+    }
+}
+
+impl TryFrom<i32> for TopLogProbs {
+    type Error = TopLogProbsError;
+
+    fn try_from(top_logprobs: i32) -> Result<Self, Self::Error> {
+        Self::new(top_logprobs)
+    }
+}
+
+/// Fill-in-the-middle completion: the model writes the text between `prompt`
+/// and `suffix`, e.g. the body of a function.
+///
+/// Only offered as a beta by DeepSeek, see [`Client::complete_fim`](super::Client::complete_fim).
+///
+/// # Examples
+/// ```
+/// # use rgi::deepseek::request::{FimCompletion, FimMaxTokens};
+/// let fim = FimCompletion {
+///     prompt: String::from("def fib(a):"),
+///     suffix: Some(String::from("    return fib(a-1) + fib(a-2)")),
+///     max_tokens: Some(FimMaxTokens::new(128).unwrap()),
+///     ..FimCompletion::default()
+/// };
+///
+/// assert_eq!(
+///     serde_json::to_string(&fim).unwrap(),
+///     r#"{"prompt":"def fib(a):","suffix":"    return fib(a-1) + fib(a-2)","max_tokens":128}"#
+/// );
+/// ```
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FimCompletion {
+    /// Falls back to [`Config::model`](super::Config::model) when `None`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<Model>,
+    pub prompt: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suffix: Option<String>,
+    /// Echoes the prompt in addition to the completion.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub echo: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<FrequencyPenalty>,
+    /// Returns the log probabilities of this many most likely tokens at every position.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logprobs: Option<TopLogProbs>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<FimMaxTokens>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<PresencePenalty>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Stop>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream: Option<bool>,
+    /// Only sent for streamed requests.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_options: Option<StreamOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<Temperature>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<TopP>,
+}
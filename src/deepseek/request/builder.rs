@@ -0,0 +1,213 @@
+//! Type-state builder for [`Chat`].
+//!
+//! The type parameters of [`ChatBuilder`] record which fields were set, so
+//! that [`ChatBuilder::build`] only exists once the request has messages,
+//! `top_logprobs` can only follow `logprobs`, and `temperature` and `top_p`
+//! exclude each other. Without a model the client's
+//! [`Config::model`](crate::deepseek::Config::model) is used.
+//!
+//! # Examples
+//! ```
+//! # use rgi::deepseek::request::{Chat, Message, Temperature, TopLogProbs};
+//! # use rgi::deepseek::Model;
+//! let chat = Chat::builder()
+//!     .model(Model::Chat)
+//!     .message(Message::User {
+//!         content: String::from("Hello"),
+//!         name: None,
+//!     })
+//!     .temperature(Temperature::CONVERSATION)
+//!     .logprobs()
+//!     .top_logprobs(TopLogProbs::new(5).unwrap())
+//!     .build();
+//!
+//! assert_eq!(chat.model, Some(Model::Chat));
+//! assert_eq!(chat.logprobs.unwrap().top_logprobs().unwrap().get(), 5);
+//! ```
+//!
+//! A request without messages can't be built:
+//! ```compile_fail
+//! # use rgi::deepseek::request::Chat;
+//! let chat = Chat::builder().model("deepseek-chat").build();
+//! ```
+//!
+//! Neither can one setting both `temperature` and `top_p`:
+//! ```compile_fail
+//! # use rgi::deepseek::request::{Chat, Temperature, TopP};
+//! let chat = Chat::builder()
+//!     .temperature(Temperature::CODING)
+//!     .top_p(TopP::new(0.9).unwrap());
+//! ```
+//!
+//! Or one asking for `top_logprobs` without `logprobs`:
+//! ```compile_fail
+//! # use rgi::deepseek::request::{Chat, TopLogProbs};
+//! let chat = Chat::builder().top_logprobs(TopLogProbs::new(5).unwrap());
+//! ```
+
+use std::marker::PhantomData;
+
+use super::{
+    Chat, FrequencyPenalty, LogProbs, MaxTokens, Message, Model, OpenRouter, PresencePenalty,
+    ResponseFormat, Stop, Temperature, Tool, ToolChoice, TopLogProbs, TopP,
+};
+
+/// No message was added yet.
+#[derive(Debug, Clone, Copy)]
+pub struct NoMessages;
+/// At least one message was added.
+#[derive(Debug, Clone, Copy)]
+pub struct HasMessages;
+
+#[derive(Debug, Clone, Copy)]
+pub struct NoLogProbs;
+#[derive(Debug, Clone, Copy)]
+pub struct HasLogProbs;
+
+/// Neither `temperature` nor `top_p` was set.
+#[derive(Debug, Clone, Copy)]
+pub struct NoSampling;
+#[derive(Debug, Clone, Copy)]
+pub struct HasTemperature;
+#[derive(Debug, Clone, Copy)]
+pub struct HasTopP;
+
+/// Builds a [`Chat`], see the [module documentation](self).
+#[derive(Debug, Clone)]
+pub struct ChatBuilder<Messages, LogProbs, Sampling> {
+    chat: Chat,
+    state: PhantomData<(Messages, LogProbs, Sampling)>,
+}
+
+impl Chat {
+    pub fn builder() -> ChatBuilder<NoMessages, NoLogProbs, NoSampling> {
+        ChatBuilder {
+            chat: Chat::default(),
+            state: PhantomData,
+        }
+    }
+}
+
+impl<Ms, L, S> ChatBuilder<Ms, L, S> {
+    fn into_state<Ms2, L2, S2>(self) -> ChatBuilder<Ms2, L2, S2> {
+        ChatBuilder {
+            chat: self.chat,
+            state: PhantomData,
+        }
+    }
+
+    pub fn message(mut self, message: Message) -> ChatBuilder<HasMessages, L, S> {
+        self.chat.messages.push(message);
+        self.into_state()
+    }
+
+    /// Appends `first` and then `rest`. Taking the first message separately
+    /// guarantees at least one is added.
+    pub fn messages(
+        mut self,
+        first: Message,
+        rest: impl IntoIterator<Item = Message>,
+    ) -> ChatBuilder<HasMessages, L, S> {
+        self.chat.messages.push(first);
+        self.chat.messages.extend(rest);
+        self.into_state()
+    }
+
+    pub fn model(mut self, model: impl Into<Model>) -> Self {
+        self.chat.model = Some(model.into());
+        self
+    }
+
+    pub fn frequency_penalty(mut self, frequency_penalty: FrequencyPenalty) -> Self {
+        self.chat.frequency_penalty = Some(frequency_penalty);
+        self
+    }
+
+    pub fn max_tokens(mut self, max_tokens: MaxTokens) -> Self {
+        self.chat.max_tokens = Some(max_tokens);
+        self
+    }
+
+    pub fn presence_penalty(mut self, presence_penalty: PresencePenalty) -> Self {
+        self.chat.presence_penalty = Some(presence_penalty);
+        self
+    }
+
+    pub fn response_format(mut self, response_format: ResponseFormat) -> Self {
+        self.chat.response_format = Some(response_format);
+        self
+    }
+
+    pub fn stop(mut self, stop: Stop) -> Self {
+        self.chat.stop = Some(stop);
+        self
+    }
+
+    pub fn stream(mut self, stream: bool) -> Self {
+        self.chat.stream = Some(stream);
+        self
+    }
+
+    pub fn tools(mut self, tools: Vec<Tool>) -> Self {
+        self.chat.tools = Some(tools);
+        self
+    }
+
+    pub fn tool_choice(mut self, tool_choice: ToolChoice) -> Self {
+        self.chat.tool_choice = Some(tool_choice);
+        self
+    }
+
+    pub fn openrouter(mut self, openrouter: OpenRouter) -> Self {
+        self.chat.openrouter = Some(openrouter);
+        self
+    }
+}
+
+impl<Ms, S> ChatBuilder<Ms, NoLogProbs, S> {
+    pub fn logprobs(mut self) -> ChatBuilder<Ms, HasLogProbs, S> {
+        self.chat.logprobs = Some(LogProbs::new());
+        self.into_state()
+    }
+}
+
+impl<Ms, S> ChatBuilder<Ms, HasLogProbs, S> {
+    pub fn top_logprobs(mut self, top_logprobs: TopLogProbs) -> Self {
+        self.chat.logprobs = Some(LogProbs::with_top(top_logprobs));
+        self
+    }
+}
+
+impl<Ms, L> ChatBuilder<Ms, L, NoSampling> {
+    pub fn temperature(mut self, temperature: Temperature) -> ChatBuilder<Ms, L, HasTemperature> {
+        self.chat.temperature = Some(temperature);
+        self.into_state()
+    }
+
+    pub fn top_p(mut self, top_p: TopP) -> ChatBuilder<Ms, L, HasTopP> {
+        self.chat.top_p = Some(top_p);
+        self.into_state()
+    }
+}
+
+impl<Ms, L> ChatBuilder<Ms, L, HasTemperature> {
+    /// Replaces the temperature set before.
+    pub fn temperature(mut self, temperature: Temperature) -> Self {
+        self.chat.temperature = Some(temperature);
+        self
+    }
+}
+
+impl<Ms, L> ChatBuilder<Ms, L, HasTopP> {
+    /// Replaces the `top_p` set before.
+    pub fn top_p(mut self, top_p: TopP) -> Self {
+        self.chat.top_p = Some(top_p);
+        self
+    }
+}
+
+impl<L, S> ChatBuilder<HasMessages, L, S> {
+    pub fn build(self) -> Chat {
+        self.chat
+    }
+}
@@ -0,0 +1,181 @@
+//! Deciding whether, and when, a failed request is sent again.
+//!
+//! The client classifies every failed attempt as a [`Failure`] and asks the
+//! configured [`RetryPolicy`] for a delay. Returning `None` gives up and hands
+//! the error of the last attempt to the caller.
+
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+/// Why a single attempt failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Failure {
+    /// The provider answered with a non-success status code.
+    Status {
+        status: StatusCode,
+        /// Delay requested through the `Retry-After` header.
+        retry_after: Option<Duration>,
+    },
+    /// The connection could not be established or was reset while sending.
+    Connection,
+    /// No response arrived in time.
+    Timeout,
+    /// Any other transport error, e.g. a TLS or redirect failure.
+    Other,
+}
+
+impl Failure {
+    /// Rate limits (429), server errors (5xx), request timeouts (408) and
+    /// connection failures are transient. Client errors such as 400, 401 or
+    /// 402 (insufficient balance) fail the same way when repeated.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rgi::deepseek::retry::Failure;
+    /// # use reqwest::StatusCode;
+    /// let status = |status| Failure::Status { status, retry_after: None };
+    ///
+    /// assert!(status(StatusCode::TOO_MANY_REQUESTS).is_retryable());
+    /// assert!(status(StatusCode::SERVICE_UNAVAILABLE).is_retryable());
+    /// assert!(Failure::Connection.is_retryable());
+    ///
+    /// assert!(!status(StatusCode::UNAUTHORIZED).is_retryable());
+    /// assert!(!status(StatusCode::PAYMENT_REQUIRED).is_retryable());
+    /// ```
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Failure::Status { status, .. } => {
+                *status == StatusCode::TOO_MANY_REQUESTS
+                    || *status == StatusCode::REQUEST_TIMEOUT
+                    || status.is_server_error()
+            }
+            Failure::Connection | Failure::Timeout => true,
+            Failure::Other => false,
+        }
+    }
+
+    pub(crate) fn from_transport(error: &reqwest::Error) -> Self {
+        if error.is_timeout() {
+            Failure::Timeout
+        } else if error.is_connect() || error.is_request() {
+            Failure::Connection
+        } else {
+            Failure::Other
+        }
+    }
+
+    pub(crate) fn from_status(status: StatusCode, headers: &HeaderMap) -> Self {
+        Failure::Status {
+            status,
+            retry_after: retry_after(headers),
+        }
+    }
+}
+
+/// Parses `Retry-After` given either in seconds or as an HTTP date.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    match value.parse::<u64>() {
+        Ok(seconds) => Some(Duration::from_secs(seconds)),
+        Err(_) => httpdate::parse_http_date(value)
+            .ok()?
+            .duration_since(SystemTime::now())
+            .ok(),
+    }
+}
+
+pub trait RetryPolicy: fmt::Debug + Send + Sync {
+    /// Returns how long to wait before the next attempt, or `None` to give up.
+    ///
+    /// `attempts` counts the attempts made so far, including the failed one,
+    /// and `elapsed` is the time since the first attempt was sent.
+    fn retry_delay(&self, failure: &Failure, attempts: u32, elapsed: Duration) -> Option<Duration>;
+}
+
+/// Never retries.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoRetry;
+
+impl RetryPolicy for NoRetry {
+    fn retry_delay(&self, _: &Failure, _: u32, _: Duration) -> Option<Duration> {
+        None
+    }
+}
+
+/// Retries transient failures with exponentially growing delays.
+///
+/// A `Retry-After` header takes precedence over the computed delay.
+///
+/// # Examples
+/// ```
+/// # use rgi::deepseek::retry::{ExponentialBackoff, Failure, RetryPolicy};
+/// # use std::time::Duration;
+/// let policy = ExponentialBackoff {
+///     jitter: false,
+///     ..ExponentialBackoff::default()
+/// };
+///
+/// let delay = |attempts| policy.retry_delay(&Failure::Connection, attempts, Duration::ZERO);
+/// assert_eq!(delay(1), Some(Duration::from_millis(500)));
+/// assert_eq!(delay(2), Some(Duration::from_secs(1)));
+/// assert_eq!(delay(4), None);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ExponentialBackoff {
+    /// Attempts made in addition to the first one.
+    pub max_retries: u32,
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    /// Gives up once the next attempt would start later than this after the first.
+    pub max_elapsed: Option<Duration>,
+    /// Randomizes every computed delay between half and its full length.
+    pub jitter: bool,
+}
+
+impl Default for ExponentialBackoff {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            max_elapsed: Some(Duration::from_secs(120)),
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy for ExponentialBackoff {
+    fn retry_delay(&self, failure: &Failure, attempts: u32, elapsed: Duration) -> Option<Duration> {
+        if !failure.is_retryable() || attempts > self.max_retries {
+            return None;
+        }
+
+        let delay = match failure {
+            Failure::Status {
+                retry_after: Some(retry_after),
+                ..
+            } => *retry_after,
+            _ => {
+                let exponent = attempts.saturating_sub(1).min(31);
+                let delay = self
+                    .initial_delay
+                    .saturating_mul(1 << exponent)
+                    .min(self.max_delay);
+
+                if self.jitter {
+                    delay.mul_f64(0.5 + fastrand::f64() / 2.0)
+                } else {
+                    delay
+                }
+            }
+        };
+
+        match self.max_elapsed {
+            Some(max_elapsed) if elapsed + delay > max_elapsed => None,
+            _ => Some(delay),
+        }
+    }
+}
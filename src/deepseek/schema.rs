@@ -0,0 +1,252 @@
+//! JSON Schema describing tool parameters and structured output.
+//!
+//! [`Schema`] models the keywords providers act on as typed fields and keeps
+//! every other keyword (`format`, `minimum`, `$defs`, ...) in [`Schema::extra`],
+//! so any valid schema survives a round trip.
+//!
+//! Schemas are either assembled by hand or, with the `schemars` feature,
+//! derived from Rust types through [`Schema::for_type`]. Doc comments on the
+//! type and its fields become descriptions.
+//!
+//! # Examples
+//! ```
+//! # use rgi::deepseek::schema::Schema;
+//! # use serde_json::json;
+//! let schema = Schema::object()
+//!     .description("A weather query")
+//!     .property("location", Schema::string().description("The city, e.g. Hangzhou"))
+//!     .property("unit", Schema::enumeration(["celsius", "fahrenheit"]))
+//!     .optional_property("days", Schema::array(Schema::integer()));
+//!
+//! assert_eq!(
+//!     serde_json::to_value(&schema).unwrap(),
+//!     json!({
+//!         "type": "object",
+//!         "description": "A weather query",
+//!         "properties": {
+//!             "location": {"type": "string", "description": "The city, e.g. Hangzhou"},
+//!             "unit": {"type": "string", "enum": ["celsius", "fahrenheit"]},
+//!             "days": {"type": "array", "items": {"type": "integer"}}
+//!         },
+//!         "required": ["location", "unit"]
+//!     })
+//! );
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Schema {
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub type_: Option<SchemaTypes>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(rename = "enum", skip_serializing_if = "Option::is_none")]
+    pub enum_: Option<Vec<Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub properties: Option<BTreeMap<String, Schema>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub required: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items: Option<Items>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub any_of: Option<Vec<Schema>>,
+    /// Keywords without a dedicated field.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// The `type` keyword, either a single type or a list such as `["string", "null"]`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SchemaTypes {
+    Single(SchemaType),
+    Multiple(Vec<SchemaType>),
+}
+
+/// The `items` keyword, either one schema for every element or, for tuples, a
+/// schema per position.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Items {
+    Single(Box<Schema>),
+    Tuple(Vec<Schema>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SchemaType {
+    String,
+    Number,
+    Integer,
+    Boolean,
+    Array,
+    Object,
+    Null,
+}
+
+impl Schema {
+    pub fn new(type_: SchemaType) -> Self {
+        Self {
+            type_: Some(SchemaTypes::Single(type_)),
+            ..Self::default()
+        }
+    }
+
+    pub fn string() -> Self {
+        Self::new(SchemaType::String)
+    }
+
+    pub fn number() -> Self {
+        Self::new(SchemaType::Number)
+    }
+
+    pub fn integer() -> Self {
+        Self::new(SchemaType::Integer)
+    }
+
+    pub fn boolean() -> Self {
+        Self::new(SchemaType::Boolean)
+    }
+
+    pub fn array(items: Schema) -> Self {
+        Self {
+            items: Some(Items::Single(Box::new(items))),
+            ..Self::new(SchemaType::Array)
+        }
+    }
+
+    /// An object without properties, see [`Schema::property`].
+    pub fn object() -> Self {
+        Self {
+            properties: Some(BTreeMap::new()),
+            required: Some(Vec::new()),
+            ..Self::new(SchemaType::Object)
+        }
+    }
+
+    /// A string restricted to `values`.
+    pub fn enumeration<I, S>(values: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            enum_: Some(
+                values
+                    .into_iter()
+                    .map(|v| Value::String(v.into()))
+                    .collect(),
+            ),
+            ..Self::string()
+        }
+    }
+
+    /// Accepts either `self` or `null`.
+    pub fn nullable(self) -> Self {
+        Self {
+            any_of: Some(vec![self, Self::new(SchemaType::Null)]),
+            ..Self::default()
+        }
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Adds a required property.
+    pub fn property(self, name: impl Into<String>, schema: Schema) -> Self {
+        let name = name.into();
+        let mut schema = self.optional_property(name.clone(), schema);
+        schema.required.get_or_insert_with(Vec::new).push(name);
+        schema
+    }
+
+    pub fn optional_property(mut self, name: impl Into<String>, schema: Schema) -> Self {
+        self.properties
+            .get_or_insert_with(BTreeMap::new)
+            .insert(name.into(), schema);
+        self
+    }
+}
+
+#[cfg(feature = "schemars")]
+impl Schema {
+    /// Derives the schema of `T` as it is deserialized.
+    ///
+    /// Subschemas are inlined where possible, only recursive types keep `$ref`s.
+    ///
+    /// # Examples
+    /// ```
+    /// # use rgi::deepseek::schema::{Schema, SchemaType, SchemaTypes};
+    /// /// A weather query
+    /// #[derive(schemars::JsonSchema)]
+    /// struct Weather {
+    ///     /// The city, e.g. Hangzhou
+    ///     location: String,
+    ///     days: Option<u8>,
+    /// }
+    ///
+    /// let schema = Schema::for_type::<Weather>();
+    /// assert_eq!(schema.description.as_deref(), Some("A weather query"));
+    /// assert_eq!(schema.required, Some(vec![String::from("location")]));
+    ///
+    /// let location = &schema.properties.as_ref().unwrap()["location"];
+    /// assert_eq!(location.type_, Some(SchemaTypes::Single(SchemaType::String)));
+    /// assert_eq!(location.description.as_deref(), Some("The city, e.g. Hangzhou"));
+    /// ```
+    pub fn for_type<T: schemars::JsonSchema + ?Sized>() -> Self {
+        let generator = schemars::generate::SchemaSettings::draft07()
+            .for_deserialize()
+            .with(|settings| {
+                settings.inline_subschemas = true;
+                settings.meta_schema = None;
+            })
+            .into_generator();
+
+        let mut value = generator.into_root_schema_for::<T>().to_value();
+
+        if let Value::Object(root) = &mut value {
+            root.remove("title");
+        }
+
+        expand_boolean_schemas(&mut value);
+
+        serde_json::from_value(value).expect("schemars produced an invalid schema object")
+    }
+}
+
+/// Replaces the boolean schemas `true` and `false` in typed positions with their
+/// object equivalents.
+#[cfg(feature = "schemars")]
+fn expand_boolean_schemas(value: &mut Value) {
+    fn expand(schema: &mut Value) {
+        match schema {
+            Value::Bool(true) => *schema = Value::Object(Map::new()),
+            Value::Bool(false) => *schema = serde_json::json!({ "not": {} }),
+            _ => expand_boolean_schemas(schema),
+        }
+    }
+
+    let Value::Object(object) = value else {
+        return;
+    };
+
+    if let Some(Value::Object(properties)) = object.get_mut("properties") {
+        properties.values_mut().for_each(expand);
+    }
+
+    match object.get_mut("items") {
+        Some(Value::Array(items)) => items.iter_mut().for_each(expand),
+        Some(items) => expand(items),
+        None => {}
+    }
+
+    if let Some(Value::Array(any_of)) = object.get_mut("anyOf") {
+        any_of.iter_mut().for_each(expand);
+    }
+}
@@ -0,0 +1,265 @@
+//! Incremental decoder for `text/event-stream` bodies.
+//!
+//! Follows the event stream interpretation rules of the HTML specification:
+//! lines may end in `\r\n`, `\n` or `\r`, `data:` fields spanning several lines
+//! are joined with `\n`, lines starting with `:` are comments (OpenRouter uses
+//! them as keep-alives) and an empty line dispatches the event.
+//!
+//! Input is buffered as raw bytes and only decoded once a full line is
+//! available, so multi-byte UTF-8 sequences split across network chunks are
+//! preserved.
+//!
+//! # Examples
+//! ```
+//! # use rgi::deepseek::sse::Decoder;
+//! let mut decoder = Decoder::new();
+//!
+//! decoder.feed(b": OPENROUTER PROCESSING\r\n\r\ndata: {\"id\"");
+//! assert!(decoder.next_event().is_none());
+//!
+//! decoder.feed(b": 1}\r\n\r\n");
+//! let event = decoder.next_event().unwrap();
+//! assert_eq!(event.data, "{\"id\": 1}");
+//! ```
+
+use bytes::{Buf, BytesMut};
+use std::time::Duration;
+
+const BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// A dispatched server-sent event.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Event {
+    /// Value of the `event:` field. `None` stands for the default `message` type.
+    pub event: Option<String>,
+    pub data: String,
+    /// The last event ID seen on the stream, which carries over between events.
+    pub id: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct Decoder {
+    buffer: BytesMut,
+    /// Number of bytes at the front of `buffer` already known to contain no line break.
+    scanned: usize,
+    /// The previous line ended in `\r`, so a leading `\n` belongs to it.
+    skip_lf: bool,
+    started: bool,
+    data: String,
+    event: Option<String>,
+    last_event_id: Option<String>,
+    retry: Option<Duration>,
+}
+
+impl Decoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends raw bytes received from the transport.
+    pub fn feed(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Returns the next complete event, or `None` if more input is needed.
+    pub fn next_event(&mut self) -> Option<Event> {
+        while let Some(line) = self.next_line() {
+            if let Some(event) = self.process_line(&line) {
+                return Some(event);
+            }
+        }
+
+        None
+    }
+
+    /// The reconnection time most recently announced through a `retry:` field.
+    pub fn retry(&self) -> Option<Duration> {
+        self.retry
+    }
+
+    fn next_line(&mut self) -> Option<BytesMut> {
+        if self.skip_lf && !self.buffer.is_empty() {
+            if self.buffer[0] == b'\n' {
+                self.buffer.advance(1);
+            }
+            self.skip_lf = false;
+        }
+
+        let Some(offset) = self.buffer[self.scanned..]
+            .iter()
+            .position(|byte| matches!(byte, b'\n' | b'\r'))
+        else {
+            self.scanned = self.buffer.len();
+            return None;
+        };
+
+        let mut line = self.buffer.split_to(self.scanned + offset);
+        self.skip_lf = self.buffer[0] == b'\r';
+        self.buffer.advance(1);
+        self.scanned = 0;
+
+        if !self.started {
+            self.started = true;
+            if line.starts_with(BOM) {
+                line.advance(BOM.len());
+            }
+        }
+
+        Some(line)
+    }
+
+    fn process_line(&mut self, line: &[u8]) -> Option<Event> {
+        if line.is_empty() {
+            return self.dispatch();
+        }
+
+        if line[0] == b':' {
+            return None;
+        }
+
+        let (field, value) = match line.iter().position(|byte| *byte == b':') {
+            Some(colon) => {
+                let value = &line[colon + 1..];
+                (&line[..colon], value.strip_prefix(b" ").unwrap_or(value))
+            }
+            None => (line, &[][..]),
+        };
+
+        let value = String::from_utf8_lossy(value);
+
+        match field {
+            b"data" => {
+                self.data.push_str(&value);
+                self.data.push('\n');
+            }
+            b"event" => self.event = Some(value.into_owned()),
+            b"id" if !value.contains('\0') => self.last_event_id = Some(value.into_owned()),
+            b"retry" if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) => {
+                if let Ok(millis) = value.parse() {
+                    self.retry = Some(Duration::from_millis(millis));
+                }
+            }
+            _ => {}
+        }
+
+        None
+    }
+
+    fn dispatch(&mut self) -> Option<Event> {
+        let event = self.event.take();
+
+        if self.data.is_empty() {
+            return None;
+        }
+
+        let mut data = std::mem::take(&mut self.data);
+        data.pop();
+
+        Some(Event {
+            event,
+            data,
+            id: self.last_event_id.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_chunks(chunks: &[&[u8]]) -> Vec<Event> {
+        let mut decoder = Decoder::new();
+        let mut events = Vec::new();
+
+        for chunk in chunks {
+            decoder.feed(chunk);
+            while let Some(event) = decoder.next_event() {
+                events.push(event);
+            }
+        }
+
+        events
+    }
+
+    fn data(events: &[Event]) -> Vec<&str> {
+        events.iter().map(|event| event.data.as_str()).collect()
+    }
+
+    #[test]
+    fn splits_on_every_line_ending() {
+        let events =
+            decode_chunks(&[b"data: lf\n\ndata: crlf\r\n\r\ndata: cr\r\rdata: mixed\r\n\n"]);
+        assert_eq!(data(&events), ["lf", "crlf", "cr", "mixed"]);
+    }
+
+    #[test]
+    fn handles_crlf_split_across_chunks() {
+        let events = decode_chunks(&[b"data: a\r", b"\n", b"\r", b"\ndata: b\r\n\r\n"]);
+        assert_eq!(data(&events), ["a", "b"]);
+    }
+
+    #[test]
+    fn joins_multi_line_data() {
+        let events = decode_chunks(&[b"data: first\ndata:second\ndata\n\n"]);
+        assert_eq!(data(&events), ["first\nsecond\n"]);
+    }
+
+    #[test]
+    fn ignores_comments_and_empty_events() {
+        let events = decode_chunks(&[
+            b": OPENROUTER PROCESSING\n\n",
+            b": OPENROUTER PROCESSING\n\n",
+            b"event: ping\n\ndata: [DONE]\n\n",
+        ]);
+        assert_eq!(
+            events,
+            [Event {
+                event: None,
+                data: String::from("[DONE]"),
+                id: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn tracks_event_id_and_retry() {
+        let mut decoder = Decoder::new();
+        decoder.feed(b"id: 7\nevent: delta\nretry: 1500\ndata: x\n\ndata: y\n\nretry: soon\n\n");
+
+        let first = decoder.next_event().unwrap();
+        assert_eq!(first.event.as_deref(), Some("delta"));
+        assert_eq!(first.id.as_deref(), Some("7"));
+
+        let second = decoder.next_event().unwrap();
+        assert_eq!(second.event, None);
+        assert_eq!(second.id.as_deref(), Some("7"));
+
+        assert!(decoder.next_event().is_none());
+        assert_eq!(decoder.retry(), Some(Duration::from_millis(1500)));
+    }
+
+    #[test]
+    fn keeps_utf8_split_across_chunks() {
+        let payload = "data: Grüße 🦀\n\n".as_bytes();
+        let chunks: Vec<&[u8]> = payload.chunks(1).collect();
+
+        let events = decode_chunks(&chunks);
+        assert_eq!(data(&events), ["Grüße 🦀"]);
+    }
+
+    #[test]
+    fn strips_leading_bom() {
+        let events = decode_chunks(&[b"\xEF\xBB", b"\xBFdata: a\n\n"]);
+        assert_eq!(data(&events), ["a"]);
+    }
+
+    #[test]
+    fn waits_for_dispatching_blank_line() {
+        let mut decoder = Decoder::new();
+        decoder.feed(b"data: partial\n");
+        assert!(decoder.next_event().is_none());
+
+        decoder.feed(b"\n");
+        assert_eq!(decoder.next_event().unwrap().data, "partial");
+    }
+}
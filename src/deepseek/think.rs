@@ -0,0 +1,250 @@
+//! Separating inline `<think>` blocks from the answer.
+//!
+//! Distilled R1 models served by some providers emit their reasoning as part of
+//! `content`, wrapped in `<think>...</think>`, instead of in `reasoning_content`.
+//! Models whose chat template already opened the block only emit the closing
+//! tag. [`ThinkSplitter`] moves the reasoning out of the content, also when a tag
+//! is split across streamed chunks. Enable it for a client with
+//! [`Config::split_think`](super::Config::split_think).
+//!
+//! # Examples
+//! ```
+//! # use rgi::deepseek::think::ThinkSplitter;
+//! let mut splitter = ThinkSplitter::new();
+//!
+//! let mut split = splitter.push("<thi");
+//! split.extend(splitter.push("nk>2 + 2 = 4</th"));
+//! split.extend(splitter.push("ink>\n\nIt's 4."));
+//! split.extend(splitter.finish());
+//!
+//! assert_eq!(split.reasoning, "2 + 2 = 4");
+//! assert_eq!(split.content, "It's 4.");
+//! ```
+
+const OPEN: &str = "<think>";
+const CLOSE: &str = "</think>";
+
+/// Text separated by a [`ThinkSplitter`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Split {
+    pub content: String,
+    pub reasoning: String,
+}
+
+impl Split {
+    pub fn extend(&mut self, other: Split) {
+        self.content.push_str(&other.content);
+        self.reasoning.push_str(&other.reasoning);
+    }
+}
+
+/// Incrementally splits text into content and `<think>` reasoning.
+///
+/// Only a block at the start of the text is reasoning, tags inside the answer are
+/// kept as they are. Text that doesn't open with `<think>` is held back until a
+/// `</think>` marks it as reasoning or [`finish`](ThinkSplitter::finish) releases
+/// it as content, unless it contains a `<think>` first.
+#[derive(Debug, Clone, Default)]
+pub struct ThinkSplitter {
+    buffer: String,
+    state: State,
+    /// Whitespace separating the closing tag from the answer is dropped.
+    trim_content: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum State {
+    /// Nothing but whitespace or the beginning of `<think>` was seen.
+    #[default]
+    Start,
+    /// The text didn't open with `<think>`, but may still be reasoning whose
+    /// opening tag was part of the prompt.
+    Unopened,
+    Thinking,
+    Answer,
+}
+
+impl ThinkSplitter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Splits `text` in one go.
+    pub fn split(text: &str) -> Split {
+        let mut splitter = Self::new();
+        let mut split = splitter.push(text);
+        split.extend(splitter.finish());
+        split
+    }
+
+    pub fn push(&mut self, text: &str) -> Split {
+        self.buffer.push_str(text);
+
+        let mut split = Split::default();
+
+        loop {
+            match self.state {
+                State::Start => {
+                    let leading = self.buffer.len() - self.buffer.trim_start().len();
+                    let rest = &self.buffer[leading..];
+
+                    if rest.starts_with(OPEN) {
+                        self.buffer.drain(..leading + OPEN.len());
+                        self.state = State::Thinking;
+                    } else if OPEN.starts_with(rest) {
+                        return split;
+                    } else {
+                        self.state = State::Unopened;
+                    }
+                }
+                State::Unopened => {
+                    let close = self.buffer.find(CLOSE);
+
+                    match self.buffer.find(OPEN) {
+                        Some(open) if close.is_none_or(|close| open < close) => {
+                            self.state = State::Answer;
+                        }
+                        _ => {
+                            let Some(close) = close else {
+                                return split;
+                            };
+
+                            split.reasoning.extend(self.buffer.drain(..close));
+                            self.end_thinking();
+                        }
+                    }
+                }
+                State::Thinking => {
+                    let Some(close) = self.buffer.find(CLOSE) else {
+                        let pending = partial_tag_len(&self.buffer, CLOSE);
+                        let end = self.buffer.len() - pending;
+                        split.reasoning.extend(self.buffer.drain(..end));
+                        return split;
+                    };
+
+                    split.reasoning.extend(self.buffer.drain(..close));
+                    self.end_thinking();
+                }
+                State::Answer => {
+                    let text = std::mem::take(&mut self.buffer);
+                    self.emit_content(&mut split, &text);
+                    return split;
+                }
+            }
+        }
+    }
+
+    /// Releases text held back as a possible tag or reasoning. An unclosed
+    /// block counts as reasoning.
+    pub fn finish(&mut self) -> Split {
+        let text = std::mem::take(&mut self.buffer);
+        let mut split = Split::default();
+
+        match self.state {
+            State::Thinking => split.reasoning = text,
+            _ => self.emit_content(&mut split, &text),
+        }
+
+        split
+    }
+
+    /// Drops the closing tag at the start of the buffer.
+    fn end_thinking(&mut self) {
+        self.buffer.drain(..CLOSE.len());
+        self.state = State::Answer;
+        self.trim_content = true;
+    }
+
+    fn emit_content(&mut self, split: &mut Split, text: &str) {
+        let text = match self.trim_content {
+            true => text.trim_start(),
+            false => text,
+        };
+
+        if !text.is_empty() {
+            self.trim_content = false;
+            split.content.push_str(text);
+        }
+    }
+}
+
+/// Length of the longest suffix of `text` that is a proper prefix of `tag`.
+fn partial_tag_len(text: &str, tag: &str) -> usize {
+    (1..tag.len())
+        .rev()
+        .find(|&len| text.ends_with(&tag[..len]))
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split_chunks(chunks: &[&str]) -> Split {
+        let mut splitter = ThinkSplitter::new();
+        let mut split = Split::default();
+
+        for chunk in chunks {
+            split.extend(splitter.push(chunk));
+        }
+
+        split.extend(splitter.finish());
+        split
+    }
+
+    #[test]
+    fn passes_through_plain_content() {
+        let split = ThinkSplitter::split("No reasoning here.");
+        assert_eq!(split.content, "No reasoning here.");
+        assert!(split.reasoning.is_empty());
+    }
+
+    #[test]
+    fn splits_tags_across_every_boundary() {
+        let text = "<think>Let me see.</think>\n\nIt's 4.";
+
+        for at in 0..=text.len() {
+            let split = split_chunks(&[&text[..at], &text[at..]]);
+            assert_eq!(split.reasoning, "Let me see.", "split at {}", at);
+            assert_eq!(split.content, "It's 4.", "split at {}", at);
+        }
+    }
+
+    #[test]
+    fn splits_unopened_reasoning_across_every_boundary() {
+        let text = "Okay, 2 + 2 = 4.\n</think>\n\nIt's 4.";
+
+        for at in 0..=text.len() {
+            let split = split_chunks(&[&text[..at], &text[at..]]);
+            assert_eq!(split.reasoning, "Okay, 2 + 2 = 4.\n", "split at {}", at);
+            assert_eq!(split.content, "It's 4.", "split at {}", at);
+        }
+    }
+
+    #[test]
+    fn keeps_tags_inside_the_answer() {
+        let text = "Use the `<think>` tag like so: <think>x</think>";
+        assert_eq!(ThinkSplitter::split(text).content, text);
+
+        let split = split_chunks(&["<think>hmm</think>", "Write </think> to close."]);
+        assert_eq!(split.reasoning, "hmm");
+        assert_eq!(split.content, "Write </think> to close.");
+    }
+
+    #[test]
+    fn holds_back_undecided_text() {
+        let mut splitter = ThinkSplitter::new();
+        assert_eq!(splitter.push("\n<thi"), Split::default());
+        assert_eq!(splitter.push("nk>a").reasoning, "a");
+
+        let mut splitter = ThinkSplitter::new();
+        assert_eq!(splitter.push("It's 4."), Split::default());
+        assert_eq!(splitter.finish().content, "It's 4.");
+    }
+
+    #[test]
+    fn releases_unfinished_text() {
+        assert_eq!(split_chunks(&["1 <thi"]).content, "1 <thi");
+        assert_eq!(split_chunks(&["<think>cut off"]).reasoning, "cut off");
+    }
+}
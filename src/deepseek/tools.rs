@@ -0,0 +1,208 @@
+//! Dispatching tool calls to typed async handlers.
+//!
+//! With the `schemars` feature, types deriving `JsonSchema` can implement
+//! [`ToolArgs::parameters`] with `Schema::for_type::<Self>()`.
+//!
+//! # Examples
+//! ```
+//! # use rgi::deepseek::completion::{FunctionCall, ToolCall, ToolType};
+//! # use rgi::deepseek::request::Message;
+//! # use rgi::deepseek::schema::Schema;
+//! # use rgi::deepseek::tools::{ToolArgs, ToolRegistry};
+//! # use serde::Deserialize;
+//! #[derive(Deserialize)]
+//! struct Weather {
+//!     location: String,
+//! }
+//!
+//! impl ToolArgs for Weather {
+//!     fn parameters() -> Schema {
+//!         Schema::object().property(
+//!             "location",
+//!             Schema::string().description("The city, e.g. Hangzhou"),
+//!         )
+//!     }
+//! }
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let mut registry = ToolRegistry::new();
+//! registry.register("get_weather", "Get the weather of a city", |args: Weather| async move {
+//!     Ok::<_, String>(format!("24℃ in {}", args.location))
+//! });
+//!
+//! let call = ToolCall {
+//!     id: String::from("call_0"),
+//!     type_: ToolType::Function,
+//!     function: FunctionCall {
+//!         name: String::from("get_weather"),
+//!         arguments: String::from(r#"{"location":"Hangzhou"}"#),
+//!     },
+//! };
+//!
+//! let Message::Tool { content, tool_call_id } = registry.execute(&call).await else {
+//!     unreachable!()
+//! };
+//! assert_eq!(tool_call_id, "call_0");
+//! assert_eq!(content, r#""24℃ in Hangzhou""#);
+//! # }
+//! ```
+
+use futures::future::{self, BoxFuture};
+use serde::{de::DeserializeOwned, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+
+use super::completion::ToolCall;
+use super::request::{Message, Tool};
+use super::schema::Schema;
+use crate::ToolError;
+
+/// Arguments of a tool, deserialized from the JSON the model produced.
+pub trait ToolArgs: DeserializeOwned {
+    /// The schema sent to the model as the tool's `parameters`.
+    fn parameters() -> Schema;
+}
+
+type Handler = Arc<dyn Fn(String) -> BoxFuture<'static, Result<String, ToolError>> + Send + Sync>;
+
+struct RegisteredTool {
+    definition: Tool,
+    handler: Handler,
+}
+
+/// Tools offered to the model, keyed by name.
+#[derive(Default, Clone)]
+pub struct ToolRegistry {
+    tools: BTreeMap<String, Arc<RegisteredTool>>,
+}
+
+impl ToolRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `handler` under `name`, replacing any tool of the same name.
+    ///
+    /// The handler's output is serialized to JSON and sent back as the tool's reply.
+    pub fn register<T, R, E, F, Fut>(
+        &mut self,
+        name: impl Into<String>,
+        description: impl Into<String>,
+        handler: F,
+    ) -> &mut Self
+    where
+        T: ToolArgs + Send + 'static,
+        R: Serialize,
+        E: fmt::Display,
+        F: Fn(T) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<R, E>> + Send + 'static,
+    {
+        let name = name.into();
+        let handler = Arc::new(handler);
+
+        let tool_name = name.clone();
+        let handler: Handler = Arc::new(move |arguments: String| {
+            let name = tool_name.clone();
+            let handler = Arc::clone(&handler);
+
+            Box::pin(async move {
+                let args = serde_json::from_str::<T>(&arguments).map_err(|source| {
+                    ToolError::InvalidArguments {
+                        name: name.clone(),
+                        source,
+                    }
+                })?;
+
+                let output = handler(args).await.map_err(|e| ToolError::Failed {
+                    name: name.clone(),
+                    message: e.to_string(),
+                })?;
+
+                serde_json::to_string(&output)
+                    .map_err(|source| ToolError::InvalidOutput { name, source })
+            })
+        });
+
+        let definition = Tool::Function {
+            description: description.into(),
+            name: name.clone(),
+            parameters: T::parameters(),
+        };
+
+        self.tools.insert(
+            name,
+            Arc::new(RegisteredTool {
+                definition,
+                handler,
+            }),
+        );
+
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tools.is_empty()
+    }
+
+    /// Tool definitions to send as [`Chat::tools`](super::request::Chat::tools).
+    pub fn definitions(&self) -> Vec<Tool> {
+        self.tools
+            .values()
+            .map(|tool| tool.definition.clone())
+            .collect()
+    }
+
+    /// Runs the handler matching `tool_call` and returns its JSON output.
+    pub async fn call(&self, tool_call: &ToolCall) -> Result<String, ToolError> {
+        let name = &tool_call.function.name;
+
+        let tool = self
+            .tools
+            .get(name)
+            .ok_or_else(|| ToolError::UnknownTool(name.clone()))?;
+
+        (tool.handler)(tool_call.function.arguments.clone()).await
+    }
+
+    /// Runs `tool_call` and wraps the outcome in the reply for the model.
+    ///
+    /// Errors are reported to the model as the reply's content, which gives it
+    /// the chance to correct its arguments.
+    pub async fn execute(&self, tool_call: &ToolCall) -> Message {
+        let content = match self.call(tool_call).await {
+            Ok(output) => output,
+            Err(e) => format!("Error: {}", e),
+        };
+
+        Message::Tool {
+            content,
+            tool_call_id: tool_call.id.clone(),
+        }
+    }
+
+    /// Runs every tool call concurrently and returns one reply per call, in the
+    /// order of `tool_calls`.
+    pub async fn execute_concurrently(&self, tool_calls: &[ToolCall]) -> Vec<Message> {
+        future::join_all(tool_calls.iter().map(|tool_call| self.execute(tool_call))).await
+    }
+
+    /// Runs every tool call in order and returns one reply per call.
+    pub async fn execute_all(&self, tool_calls: &[ToolCall]) -> Vec<Message> {
+        let mut replies = Vec::with_capacity(tool_calls.len());
+
+        for tool_call in tool_calls {
+            replies.push(self.execute(tool_call).await);
+        }
+
+        replies
+    }
+}
+
+impl fmt::Debug for ToolRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.tools.keys()).finish()
+    }
+}
@@ -0,0 +1,240 @@
+use reqwest::StatusCode;
+use std::time::Duration;
+use thiserror::Error;
+
+use crate::deepseek::agent::Transcript;
+use crate::deepseek::completion::{ApiError, ErrorResponse, FinishReason};
+use crate::deepseek::request::{
+    FimMaxTokenError, FrequencyPenaltyError, MaxTokenError, PrefixError, PresencePenaltyError,
+    ReasonerError, ReasoningContentError, ResponseFormatError, StopError, TemperatureError,
+    TopLogProbsError, TopPError,
+};
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    /// The request could not be sent or the response body could not be read.
+    #[error(transparent)]
+    Transport(#[from] reqwest::Error),
+    /// The provider rejected the request or failed mid-stream with an error body.
+    ///
+    /// `status` is `None` for errors sent as an in-band stream event.
+    #[error("provider error: {error}")]
+    Api {
+        status: Option<StatusCode>,
+        error: ApiError,
+    },
+    /// The provider answered with a non-success status code and an unrecognized body.
+    #[error("provider returned {status}: {body}")]
+    Status { status: StatusCode, body: String },
+    /// The response body, or a single stream event, is not the expected JSON.
+    #[error("failed to decode response: {source}")]
+    Decode {
+        #[source]
+        source: serde_json::Error,
+        body: String,
+    },
+    #[error(transparent)]
+    Stream(#[from] StreamError),
+    #[error(transparent)]
+    Validation(#[from] ValidationError),
+    #[error(transparent)]
+    Build(#[from] BuildError),
+    #[error(transparent)]
+    Tool(#[from] ToolError),
+    #[error(transparent)]
+    Agent(#[from] AgentError),
+    /// The configured provider doesn't offer the requested feature.
+    #[error("not supported by the configured provider: {0}")]
+    Unsupported(&'static str),
+    /// The request failed after it was sent more than once.
+    #[error("{source} (after {attempts} attempts)")]
+    Retried {
+        attempts: u32,
+        #[source]
+        source: Box<Error>,
+    },
+}
+
+impl Error {
+    /// The HTTP status code, if the provider answered at all.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Error::Transport(e) => e.status(),
+            Error::Api { status, .. } => *status,
+            Error::Status { status, .. } => Some(*status),
+            Error::Retried { source, .. } => source.status(),
+            _ => None,
+        }
+    }
+
+    /// The error details reported by the provider, if any.
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            Error::Api { error, .. } => Some(error),
+            Error::Retried { source, .. } => source.api_error(),
+            _ => None,
+        }
+    }
+
+    pub fn is_timeout(&self) -> bool {
+        match self {
+            Error::Transport(e) => e.is_timeout(),
+            Error::Stream(StreamError::IdleTimeout(_)) => true,
+            Error::Retried { source, .. } => source.is_timeout(),
+            _ => false,
+        }
+    }
+
+    /// Number of requests sent before giving up.
+    pub fn attempts(&self) -> u32 {
+        match self {
+            Error::Retried { attempts, .. } => *attempts,
+            _ => 1,
+        }
+    }
+
+    /// Interprets a body that could not be decoded as the expected type.
+    ///
+    /// Falls back to [`Error::Decode`] unless the body is an error envelope.
+    pub(crate) fn from_body(
+        status: Option<StatusCode>,
+        source: serde_json::Error,
+        body: String,
+    ) -> Self {
+        match serde_json::from_str::<ErrorResponse>(&body) {
+            Ok(ErrorResponse { error }) => Error::Api { status, error },
+            Err(_) => Error::Decode { source, body },
+        }
+    }
+}
+
+#[derive(Error, Debug, Clone, Copy)]
+pub enum StreamError {
+    #[error("stream closed before [DONE] was received")]
+    UnexpectedEof,
+    #[error("no data received for {0:?}")]
+    IdleTimeout(Duration),
+    #[error("no chunks were received")]
+    Empty,
+    #[error("choice {0} ended without a finish_reason")]
+    MissingFinishReason(u32),
+}
+
+#[derive(Error, Debug)]
+pub enum BuildError {
+    #[error("no API key was provided")]
+    MissingApiKey,
+    #[error("environment variable {0} is not set")]
+    MissingEnv(String),
+    #[error("API key contains characters that are not allowed in a header")]
+    InvalidApiKey,
+    #[error("invalid header {0}")]
+    InvalidHeader(String),
+    #[error("failed to build HTTP client: {0}")]
+    Http(#[source] reqwest::Error),
+}
+
+#[derive(Error, Debug)]
+pub enum ToolError {
+    #[error("no tool named {0} is registered")]
+    UnknownTool(String),
+    #[error("invalid arguments for tool {name}: {source}")]
+    InvalidArguments {
+        name: String,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("tool {name} failed: {message}")]
+    Failed { name: String, message: String },
+    #[error("output of tool {name} is not serializable: {source}")]
+    InvalidOutput {
+        name: String,
+        #[source]
+        source: serde_json::Error,
+    },
+}
+
+#[derive(Error, Debug)]
+pub enum AgentError {
+    #[error("agent did not finish within {max_steps} steps")]
+    MaxSteps {
+        max_steps: usize,
+        transcript: Box<Transcript>,
+    },
+    #[error("model stopped with finish reason {reason:?}")]
+    Stopped {
+        reason: FinishReason,
+        transcript: Box<Transcript>,
+    },
+    #[error("completion contained no choices")]
+    EmptyResponse,
+}
+
+impl AgentError {
+    /// The steps taken before the run was aborted, if any.
+    pub fn transcript(&self) -> Option<&Transcript> {
+        match self {
+            AgentError::MaxSteps { transcript, .. } | AgentError::Stopped { transcript, .. } => {
+                Some(transcript)
+            }
+            AgentError::EmptyResponse => None,
+        }
+    }
+}
+
+#[derive(Error, Debug, Clone)]
+pub enum ValidationError {
+    #[error(transparent)]
+    MaxTokens(#[from] MaxTokenError),
+    #[error(transparent)]
+    FimMaxTokens(#[from] FimMaxTokenError),
+    #[error(transparent)]
+    FrequencyPenalty(#[from] FrequencyPenaltyError),
+    #[error(transparent)]
+    PresencePenalty(#[from] PresencePenaltyError),
+    #[error(transparent)]
+    Temperature(#[from] TemperatureError),
+    #[error(transparent)]
+    TopP(#[from] TopPError),
+    #[error(transparent)]
+    ResponseFormat(#[from] ResponseFormatError),
+    #[error(transparent)]
+    TopLogProbs(#[from] TopLogProbsError),
+    #[error(transparent)]
+    Stop(#[from] StopError),
+    #[error(transparent)]
+    Prefix(#[from] PrefixError),
+    #[error(transparent)]
+    Reasoner(#[from] ReasonerError),
+    #[error(transparent)]
+    ReasoningContent(#[from] ReasoningContentError),
+}
+
+macro_rules! impl_from_validation_error {
+    ($($error:ty),*) => {
+        $(
+            impl From<$error> for Error {
+                fn from(e: $error) -> Self {
+                    Error::Validation(e.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_validation_error!(
+    MaxTokenError,
+    FimMaxTokenError,
+    FrequencyPenaltyError,
+    PresencePenaltyError,
+    TemperatureError,
+    TopPError,
+    ResponseFormatError,
+    TopLogProbsError,
+    StopError,
+    PrefixError,
+    ReasonerError,
+    ReasoningContentError
+);
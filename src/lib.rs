@@ -1 +1,4 @@
 pub mod deepseek;
+mod error;
+
+pub use error::{AgentError, BuildError, Error, Result, StreamError, ToolError, ValidationError};
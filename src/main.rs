@@ -1,19 +1,17 @@
-use std::env;
-
+use futures::StreamExt;
 use rgi::deepseek::{
     self,
     request::{Chat, Message},
-    stream,
 };
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     dotenv::dotenv().ok();
 
-    let client = deepseek::Client::new(
-        &env::var("DEEPSEEK_KEY").expect("Missing DEEPSEEK_KEY"),
-        deepseek::Config::default(),
-    );
+    let client = deepseek::Client::builder()
+        .api_key_env("DEEPSEEK_KEY")
+        .config(deepseek::Config::default())
+        .build()?;
 
     let messages = vec![Message::User {
         content: String::from("What's your favorite kind of synthetic data?"),
@@ -27,7 +25,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         ..Chat::default()
     };
 
-    stream(&client, chat).await?;
+    let mut chunks = client.stream(chat).await?;
+
+    while let Some(chunk) = chunks.next().await {
+        println!("{:#?}", chunk?);
+    }
 
     Ok(())
 }
@@ -0,0 +1,158 @@
+use rgi::deepseek::agent::Agent;
+use rgi::deepseek::request::{Chat, Message};
+use rgi::deepseek::schema::Schema;
+use rgi::deepseek::tools::{ToolArgs, ToolRegistry};
+use rgi::deepseek::Config;
+use rgi::{AgentError, Error};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Barrier;
+use wiremock::matchers::{body_partial_json, method};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+use common::client;
+
+const TOOL_CALLS: &str = r#"{
+    "id": "c1",
+    "object": "chat.completion",
+    "created": 1737000000,
+    "model": "deepseek-chat",
+    "system_fingerprint": "fp_1",
+    "choices": [{
+        "index": 0,
+        "message": {
+            "role": "assistant",
+            "content": "",
+            "tool_calls": [
+                {"id": "call_0", "type": "function", "function": {"name": "get_weather", "arguments": "{\"location\":\"Hangzhou\"}"}},
+                {"id": "call_1", "type": "function", "function": {"name": "get_weather", "arguments": "{\"location\":\"Beijing\"}"}}
+            ]
+        },
+        "finish_reason": "tool_calls"
+    }],
+    "usage": {"prompt_tokens": 20, "completion_tokens": 10, "total_tokens": 30}
+}"#;
+
+const ANSWER: &str = r#"{
+    "id": "c2",
+    "object": "chat.completion",
+    "created": 1737000001,
+    "model": "deepseek-chat",
+    "system_fingerprint": "fp_1",
+    "choices": [{
+        "index": 0,
+        "message": {"role": "assistant", "content": "Hangzhou is warmer."},
+        "finish_reason": "stop"
+    }],
+    "usage": {"prompt_tokens": 50, "completion_tokens": 5, "total_tokens": 55}
+}"#;
+
+#[derive(serde::Deserialize)]
+struct Location {
+    location: String,
+}
+
+impl ToolArgs for Location {
+    fn parameters() -> Schema {
+        Schema::object().property("location", Schema::string())
+    }
+}
+
+/// Both calls have to wait for each other, so sequential execution never finishes.
+fn weather_tools() -> ToolRegistry {
+    let barrier = Arc::new(Barrier::new(2));
+
+    let mut tools = ToolRegistry::new();
+    tools.register("get_weather", "Get the weather", move |args: Location| {
+        let barrier = Arc::clone(&barrier);
+        async move {
+            barrier.wait().await;
+            Ok::<_, String>(format!("24℃ in {}", args.location))
+        }
+    });
+    tools
+}
+
+fn chat() -> Chat {
+    Chat {
+        messages: vec![Message::User {
+            content: String::from("Where is it warmer, Hangzhou or Beijing?"),
+            name: None,
+        }],
+        ..Chat::default()
+    }
+}
+
+#[tokio::test]
+async fn runs_tool_calls_until_stop() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(body_partial_json(serde_json::json!({
+            "tools": [{"type": "function", "function": {"name": "get_weather"}}]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_string(TOOL_CALLS))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(body_partial_json(serde_json::json!({
+            "messages": [{}, {"role": "assistant"}, {"role": "tool", "tool_call_id": "call_0"}, {"role": "tool", "tool_call_id": "call_1"}]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_string(ANSWER))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let steps = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&steps);
+
+    let agent =
+        Agent::new(client(&server, Config::deepseek()), weather_tools()).on_step(move |step| {
+            assert_eq!(step.index, counter.fetch_add(1, Ordering::SeqCst));
+        });
+
+    let transcript = tokio::time::timeout(Duration::from_secs(5), agent.run(chat()))
+        .await
+        .expect("tool calls were not executed concurrently")
+        .unwrap();
+
+    assert_eq!(steps.load(Ordering::SeqCst), 2);
+    assert_eq!(transcript.steps.len(), 2);
+    assert_eq!(transcript.steps[0].tool_calls().len(), 2);
+    assert_eq!(transcript.messages.len(), 5);
+    assert_eq!(transcript.content(), Some("Hangzhou is warmer."));
+    assert_eq!(transcript.total_tokens(), 85);
+
+    let Message::Tool { content, .. } = &transcript.messages[3] else {
+        panic!("expected a tool reply");
+    };
+    assert_eq!(content, r#""24℃ in Beijing""#);
+}
+
+#[tokio::test]
+async fn gives_up_after_max_steps() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(TOOL_CALLS))
+        .expect(3)
+        .mount(&server)
+        .await;
+
+    let error = Agent::new(client(&server, Config::deepseek()), weather_tools())
+        .max_steps(3)
+        .run(chat())
+        .await
+        .unwrap_err();
+
+    let Error::Agent(error @ AgentError::MaxSteps { max_steps: 3, .. }) = error else {
+        panic!("expected AgentError::MaxSteps, got {:?}", error);
+    };
+    assert_eq!(error.transcript().unwrap().steps.len(), 3);
+}
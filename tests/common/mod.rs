@@ -0,0 +1,57 @@
+//! Helpers shared by the integration tests.
+
+// Every test crate compiles this module but only uses part of it.
+#![allow(dead_code)]
+
+use rgi::deepseek::{Client, Config};
+use serde_json::{json, Value};
+use wiremock::MockServer;
+
+/// A client for `preset` that talks to `server`.
+pub fn client(server: &MockServer, preset: Config) -> Client {
+    Client::new(
+        "test-key",
+        Config {
+            base_url: server.uri(),
+            ..preset
+        },
+    )
+    .unwrap()
+}
+
+/// A finished chat completion answering `content`.
+pub fn completion(content: &str) -> Value {
+    json!({
+        "id": "c1",
+        "object": "chat.completion",
+        "created": 1737000000,
+        "model": "deepseek-chat",
+        "system_fingerprint": "fp_1",
+        "choices": [{
+            "index": 0,
+            "message": {"role": "assistant", "content": content},
+            "finish_reason": "stop"
+        }],
+        "usage": {"prompt_tokens": 3, "completion_tokens": 1, "total_tokens": 4}
+    })
+}
+
+/// A chat completion chunk of the first choice.
+pub fn chunk(delta: Value, finish_reason: Option<&str>) -> Value {
+    json!({
+        "id": "c1",
+        "object": "chat.completion.chunk",
+        "created": 1737000000,
+        "model": "deepseek-chat",
+        "choices": [{"index": 0, "delta": delta, "finish_reason": finish_reason}]
+    })
+}
+
+/// A server-sent event body delivering `chunks`, terminated by `[DONE]`.
+pub fn events(chunks: impl IntoIterator<Item = Value>) -> String {
+    chunks
+        .into_iter()
+        .map(|chunk| format!("data: {}\n\n", chunk))
+        .chain([String::from("data: [DONE]\n\n")])
+        .collect()
+}
@@ -0,0 +1,703 @@
+use futures::StreamExt;
+use rgi::deepseek::request::{
+    Chat, LogProbs, Message, PrefixError, ReasonerError, ReasoningContentError,
+    ResponseFormatError, Temperature,
+};
+use rgi::deepseek::retry::{ExponentialBackoff, NoRetry};
+use rgi::deepseek::{Client, Config, Model};
+use rgi::{Error, StreamError, ValidationError};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use wiremock::matchers::{body_partial_json, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+use common::{chunk, client, completion, events};
+
+const EVENTS: &str = concat!(
+    ": OPENROUTER PROCESSING\n\n",
+    r#"data: {"id":"c1","object":"chat.completion.chunk","created":1737000000,"model":"deepseek-chat","choices":[{"index":0,"delta":{"role":"assistant","content":"Hel"}}]}"#,
+    "\n\n",
+    r#"data: {"id":"c1","object":"chat.completion.chunk","created":1737000000,"model":"deepseek-chat","choices":[{"index":0,"delta":{"content":"lo"},"finish_reason":"stop"}]}"#,
+    "\n\n",
+    "data: [DONE]\n\n",
+);
+
+async fn mock_server(api_path: &str) -> MockServer {
+    let server = MockServer::start().await;
+    let endpoint = format!("{}/chat/completions", api_path);
+
+    Mock::given(method("POST"))
+        .and(path(endpoint.as_str()))
+        .and(header("authorization", "Bearer test-key"))
+        .and(body_partial_json(serde_json::json!({ "stream": false })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(completion("Hello")))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path(endpoint.as_str()))
+        .and(body_partial_json(serde_json::json!({ "stream": true })))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(EVENTS, "text/event-stream"))
+        .mount(&server)
+        .await;
+
+    server
+}
+
+fn chat(stream: bool) -> Chat {
+    Chat {
+        stream: Some(stream),
+        ..Chat::default()
+    }
+}
+
+#[tokio::test]
+async fn complete_uses_preset_endpoint() {
+    for (api_path, preset) in [("", Config::deepseek()), ("/api/v1", Config::openrouter())] {
+        let server = mock_server(api_path).await;
+
+        let object = client(&server, preset).complete(chat(false)).await.unwrap();
+
+        assert_eq!(object.choices[0].message.content.as_deref(), Some("Hello"));
+    }
+}
+
+#[tokio::test]
+async fn stream_uses_preset_endpoint() {
+    for (api_path, preset) in [("", Config::deepseek()), ("/api/v1", Config::openrouter())] {
+        let server = mock_server(api_path).await;
+
+        let chunks: Vec<_> = client(&server, preset)
+            .stream(chat(true))
+            .await
+            .unwrap()
+            .collect()
+            .await;
+
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks.iter().all(Result::is_ok));
+    }
+}
+
+#[tokio::test]
+async fn complete_and_stream_converge() {
+    let server = mock_server("").await;
+    let client = client(&server, Config::deepseek());
+
+    let streamed = client
+        .stream(chat(true))
+        .await
+        .unwrap()
+        .collect_completion()
+        .await
+        .unwrap();
+
+    let completed = client.complete(chat(false)).await.unwrap();
+
+    assert_eq!(
+        streamed.choices[0].message.content,
+        completed.choices[0].message.content
+    );
+}
+
+#[tokio::test]
+async fn complete_never_streams() {
+    let server = mock_server("").await;
+
+    let object = client(&server, Config::deepseek())
+        .complete(chat(true))
+        .await
+        .unwrap();
+
+    assert_eq!(object.choices[0].message.content.as_deref(), Some("Hello"));
+}
+
+#[tokio::test]
+async fn client_is_shared_across_tasks() {
+    let server = mock_server("").await;
+    let client = client(&server, Config::deepseek());
+
+    let tasks: Vec<_> = (0..4)
+        .map(|_| {
+            let client = client.clone();
+            tokio::spawn(async move { client.complete(chat(false)).await })
+        })
+        .collect();
+
+    for task in tasks {
+        assert!(task.await.unwrap().is_ok());
+    }
+}
+
+#[tokio::test]
+async fn stalled_response_hits_idle_timeout() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_raw(EVENTS, "text/event-stream")
+                .set_delay(Duration::from_secs(5)),
+        )
+        .mount(&server)
+        .await;
+
+    let client = client(
+        &server,
+        Config {
+            idle_timeout: Duration::from_millis(100),
+            retry_policy: Arc::new(NoRetry),
+            ..Config::deepseek()
+        },
+    );
+
+    let error = client.stream(chat(true)).await.err().unwrap();
+    assert!(error.is_timeout());
+}
+
+#[tokio::test]
+async fn stream_stalling_between_events_hits_idle_timeout() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut request = [0; 4096];
+        let _ = socket.read(&mut request).await.unwrap();
+
+        let event = EVENTS.split_inclusive("\n\n").nth(1).unwrap();
+        let response = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\ntransfer-encoding: chunked\r\n\r\n{:x}\r\n{}\r\n",
+            event.len(),
+            event
+        );
+        socket.write_all(response.as_bytes()).await.unwrap();
+
+        // Keep the connection open without sending anything else.
+        tokio::time::sleep(Duration::from_secs(5)).await;
+    });
+
+    let client = Client::new(
+        "test-key",
+        Config {
+            base_url: format!("http://{}", address),
+            idle_timeout: Duration::from_millis(200),
+            retry_policy: Arc::new(NoRetry),
+            ..Config::deepseek()
+        },
+    )
+    .unwrap();
+
+    let mut stream = client.stream(chat(true)).await.unwrap();
+
+    let chunk = stream.next().await.unwrap().unwrap();
+    assert_eq!(chunk.choices[0].delta.content.as_deref(), Some("Hel"));
+
+    let error = stream.next().await.unwrap().unwrap_err();
+    assert!(matches!(
+        error,
+        Error::Stream(StreamError::IdleTimeout(timeout)) if timeout == Duration::from_millis(200)
+    ));
+    assert!(stream.next().await.is_none());
+}
+
+#[tokio::test]
+async fn errors_inside_stream_end_it() {
+    let server = MockServer::start().await;
+
+    let events = concat!(
+        r#"data: {"id":"c1","object":"chat.completion.chunk","created":1737000000,"model":"deepseek-chat","choices":[{"index":0,"delta":{"role":"assistant","content":"Hel"}}]}"#,
+        "\n\n",
+        r#"data: {"id":"c1","object":"chat.completion.chunk","created":1737000000,"model":"deepseek-chat","choices":[{"index":0,"delta":{"content":""},"finish_reason":null}],"error":{"code":502,"message":"Provider disconnected"}}"#,
+        "\n\n",
+        "data: [DONE]\n\n",
+    );
+
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(events, "text/event-stream"))
+        .mount(&server)
+        .await;
+
+    let chunks: Vec<_> = client(&server, Config::openrouter())
+        .stream(chat(true))
+        .await
+        .unwrap()
+        .collect()
+        .await;
+
+    assert_eq!(chunks.len(), 2);
+    assert!(chunks[0].is_ok());
+    assert!(matches!(
+        &chunks[1],
+        Err(Error::Api { status: None, error }) if error.message == "Provider disconnected"
+    ));
+}
+
+#[tokio::test]
+async fn rate_limits_are_retried_after_delay() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .respond_with(
+            ResponseTemplate::new(429)
+                .insert_header("retry-after", "0")
+                .set_body_string(r#"{"error":{"code":429,"message":"Rate limit exceeded"}}"#),
+        )
+        .up_to_n_times(2)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(completion("Hello")))
+        .mount(&server)
+        .await;
+
+    let object = client(&server, Config::deepseek())
+        .complete(chat(false))
+        .await
+        .unwrap();
+
+    assert_eq!(object.attempts, 3);
+}
+
+#[tokio::test]
+async fn client_errors_are_not_retried() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(402).set_body_string(
+            r#"{"error":{"message":"Insufficient Balance","type":"unknown_error","code":"invalid_request_error"}}"#,
+        ))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let error = client(&server, Config::deepseek())
+        .complete(chat(false))
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.attempts(), 1);
+    assert_eq!(error.status().map(|status| status.as_u16()), Some(402));
+    assert_eq!(error.api_error().unwrap().message, "Insufficient Balance");
+}
+
+#[tokio::test]
+async fn exhausted_retries_report_attempts() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(3)
+        .mount(&server)
+        .await;
+
+    let error = client(
+        &server,
+        Config {
+            retry_policy: Arc::new(ExponentialBackoff {
+                max_retries: 2,
+                initial_delay: Duration::from_millis(1),
+                ..ExponentialBackoff::default()
+            }),
+            ..Config::deepseek()
+        },
+    )
+    .complete(chat(false))
+    .await
+    .unwrap_err();
+
+    assert_eq!(error.attempts(), 3);
+    assert_eq!(error.status().map(|status| status.as_u16()), Some(503));
+}
+
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct Answer {
+    question: String,
+    answer: u32,
+}
+
+fn json_chat(prompt: &str) -> Chat {
+    Chat {
+        messages: vec![Message::User {
+            content: String::from(prompt),
+            name: None,
+        }],
+        ..Chat::default()
+    }
+}
+
+#[tokio::test]
+async fn complete_json_repairs_invalid_output() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(body_partial_json(serde_json::json!({
+            "response_format": {"type": "json_object"}
+        })))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(completion(r#"{"question": "1 + 1", "answer": "two"}"#)),
+        )
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(body_partial_json(serde_json::json!({
+            "messages": [{}, {"role": "assistant"}, {"role": "user"}]
+        })))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(completion(r#"{"question": "1 + 1", "answer": 2}"#)),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let answer: Answer = client(&server, Config::deepseek())
+        .complete_json(json_chat("What is 1 + 1? Reply in JSON."), 1)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        answer,
+        Answer {
+            question: String::from("1 + 1"),
+            answer: 2
+        }
+    );
+}
+
+#[tokio::test]
+async fn complete_json_gives_up_after_max_repairs() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(completion("two")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let error = client(&server, Config::deepseek())
+        .complete_json::<Answer>(json_chat("What is 1 + 1? Reply in JSON."), 0)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(error, Error::Decode { body, .. } if body == "two"));
+}
+
+#[tokio::test]
+async fn complete_json_requires_json_prompt() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(completion("Hello")))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let error = client(&server, Config::deepseek())
+        .complete_json::<Answer>(json_chat("What is 1 + 1?"), 2)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(
+        error,
+        Error::Validation(ValidationError::ResponseFormat(
+            ResponseFormatError::MissingJsonPrompt
+        ))
+    ));
+}
+
+#[tokio::test]
+async fn stream_requests_usage() {
+    let server = MockServer::start().await;
+
+    let events = concat!(
+        r#"data: {"id":"c1","object":"chat.completion.chunk","created":1737000000,"model":"deepseek-chat","choices":[{"index":0,"delta":{"role":"assistant","content":"Hello"},"finish_reason":"stop"}]}"#,
+        "\n\n",
+        r#"data: {"id":"c1","object":"chat.completion.chunk","created":1737000000,"model":"deepseek-chat","choices":[],"usage":{"prompt_tokens":3,"completion_tokens":1,"total_tokens":4}}"#,
+        "\n\n",
+        "data: [DONE]\n\n",
+    );
+
+    Mock::given(method("POST"))
+        .and(body_partial_json(serde_json::json!({
+            "stream": true,
+            "stream_options": {"include_usage": true}
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(events, "text/event-stream"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let object = client(&server, Config::deepseek())
+        .stream(chat(false))
+        .await
+        .unwrap()
+        .collect_completion()
+        .await
+        .unwrap();
+
+    assert_eq!(object.usage.total_tokens, Some(4));
+}
+
+#[tokio::test]
+async fn config_model_is_the_default() {
+    let server = MockServer::start().await;
+
+    for model in ["deepseek-chat", "deepseek-reasoner"] {
+        Mock::given(method("POST"))
+            .and(body_partial_json(serde_json::json!({ "model": model })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(completion("Hello")))
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    let client = client(&server, Config::deepseek());
+
+    client.complete(chat(false)).await.unwrap();
+    client
+        .complete(Chat {
+            model: Some(Model::Reasoner),
+            ..chat(false)
+        })
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn prefix_completion_uses_beta_endpoint() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/beta/chat/completions"))
+        .and(body_partial_json(serde_json::json!({
+            "messages": [{}, {"role": "assistant", "content": "```python\n", "prefix": true}]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(completion("Hello")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = client(&server, Config::deepseek());
+    let prompt = json_chat("Write quick sort in Python.");
+
+    client
+        .complete(prompt.clone().prefix("```python\n"))
+        .await
+        .unwrap();
+
+    let mut misplaced = prompt.prefix("```python\n");
+    misplaced.messages.push(Message::User {
+        content: String::from("Go on."),
+        name: None,
+    });
+
+    assert!(matches!(
+        client.complete(misplaced).await.unwrap_err(),
+        Error::Validation(ValidationError::Prefix(PrefixError::NotLast))
+    ));
+}
+
+#[tokio::test]
+async fn reasoner_rejects_unsupported_parameters() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(completion("Hello")))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let client = client(
+        &server,
+        Config {
+            model: Model::Reasoner,
+            ..Config::deepseek()
+        },
+    );
+
+    let error = client
+        .complete(Chat {
+            logprobs: Some(LogProbs::new()),
+            ..chat(false)
+        })
+        .await
+        .unwrap_err();
+
+    assert!(matches!(
+        error,
+        Error::Validation(ValidationError::Reasoner(ReasonerError::LogProbs(
+            Model::Reasoner
+        )))
+    ));
+}
+
+#[tokio::test]
+async fn openrouter_reasoning_models_accept_sampling() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(body_partial_json(serde_json::json!({
+            "model": "deepseek/deepseek-r1-distill-llama-70b",
+            "temperature": 0.0
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(completion("Hello")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client(&server, Config::openrouter())
+        .complete(Chat {
+            temperature: Some(Temperature::CODING),
+            ..chat(false)
+        })
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn echoed_reasoning_is_rejected() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(completion("Hello")))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = client(&server, Config::deepseek());
+    let answer = |prefix| Message::Assistant {
+        content: Some(String::from("9.8")),
+        name: None,
+        tool_calls: None,
+        prefix,
+        reasoning_content: Some(String::from("Compare the tenths...")),
+    };
+
+    let echoed = Chat {
+        messages: vec![
+            answer(None),
+            Message::User {
+                content: String::from("Are you sure?"),
+                name: None,
+            },
+        ],
+        ..chat(false)
+    };
+    assert!(matches!(
+        client.complete(echoed).await.unwrap_err(),
+        Error::Validation(ValidationError::ReasoningContent(
+            ReasoningContentError::Echoed(0)
+        ))
+    ));
+
+    let prefix = Chat {
+        messages: vec![answer(Some(true))],
+        model: Some(Model::Reasoner),
+        ..chat(false)
+    };
+    client.complete(prefix).await.unwrap();
+}
+
+#[tokio::test]
+async fn splits_inline_think_blocks() {
+    let server = MockServer::start().await;
+
+    let delta = |content: &str, finish_reason| {
+        chunk(serde_json::json!({ "content": content }), finish_reason)
+    };
+
+    let events = events([
+        delta("<thi", None),
+        delta("nk>1 + 1 is 2.</", None),
+        delta("think>\n\nIt's 2.", Some("stop")),
+    ]);
+
+    Mock::given(method("POST"))
+        .and(body_partial_json(serde_json::json!({ "stream": true })))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(events, "text/event-stream"))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(body_partial_json(serde_json::json!({ "stream": false })))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(completion("<think>1 + 1 is 2.</think>\n\nIt's 2.")),
+        )
+        .mount(&server)
+        .await;
+
+    let client = client(
+        &server,
+        Config {
+            split_think: true,
+            ..Config::openrouter()
+        },
+    );
+
+    let streamed = client
+        .stream(chat(true))
+        .await
+        .unwrap()
+        .collect_completion()
+        .await
+        .unwrap();
+    let completed = client.complete(chat(false)).await.unwrap();
+
+    for object in [streamed, completed] {
+        let message = &object.choices[0].message;
+        assert_eq!(message.content.as_deref(), Some("It's 2."));
+        assert_eq!(message.reasoning_content.as_deref(), Some("1 + 1 is 2."));
+    }
+}
+
+#[tokio::test]
+async fn split_think_releases_held_text_at_the_end() {
+    let server = MockServer::start().await;
+
+    let events = events([
+        chunk(serde_json::json!({ "content": "It's" }), None),
+        chunk(serde_json::json!({ "content": " 2." }), None),
+    ]);
+
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(events, "text/event-stream"))
+        .mount(&server)
+        .await;
+
+    let chunks: Vec<_> = client(
+        &server,
+        Config {
+            split_think: true,
+            ..Config::openrouter()
+        },
+    )
+    .stream(chat(true))
+    .await
+    .unwrap()
+    .map(Result::unwrap)
+    .collect()
+    .await;
+
+    let (mut content, mut reasoning) = (String::new(), String::new());
+    for chunk in &chunks {
+        let delta = &chunk.choices[0].delta;
+        content.push_str(delta.content.as_deref().unwrap_or_default());
+        reasoning.push_str(delta.reasoning_content.as_deref().unwrap_or_default());
+    }
+
+    // Without a `<think>` the answer is held back, and no chunk finished it.
+    assert_eq!(chunks.len(), 3);
+    assert_eq!(content, "It's 2.");
+    assert!(reasoning.is_empty());
+}
@@ -0,0 +1,103 @@
+use futures::StreamExt;
+use rgi::deepseek::request::FimCompletion;
+use rgi::deepseek::Config;
+use rgi::Error;
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+use common::client;
+
+const COMPLETION: &str = r#"{
+    "id": "f1",
+    "object": "text_completion",
+    "created": 1737000000,
+    "model": "deepseek-chat",
+    "system_fingerprint": "fp_1",
+    "choices": [{
+        "index": 0,
+        "text": "\n    if a < 2:\n        return a\n",
+        "logprobs": {"tokens": ["\n"], "token_logprobs": [-0.01], "top_logprobs": [{"\n": -0.01}], "text_offset": [11]},
+        "finish_reason": "stop"
+    }],
+    "usage": {"prompt_tokens": 20, "completion_tokens": 12, "total_tokens": 32}
+}"#;
+
+const EVENTS: &str = concat!(
+    r#"data: {"id":"f1","object":"text_completion","created":1737000000,"model":"deepseek-chat","choices":[{"index":0,"text":"\n    if a < 2:","logprobs":null,"finish_reason":null}]}"#,
+    "\n\n",
+    r#"data: {"id":"f1","object":"text_completion","created":1737000000,"model":"deepseek-chat","choices":[{"index":0,"text":"\n        return a\n","logprobs":null,"finish_reason":"stop"}]}"#,
+    "\n\n",
+    "data: [DONE]\n\n",
+);
+
+fn fim() -> FimCompletion {
+    FimCompletion {
+        prompt: String::from("def fib(a):"),
+        suffix: Some(String::from("    return fib(a-1) + fib(a-2)")),
+        ..FimCompletion::default()
+    }
+}
+
+#[tokio::test]
+async fn completes_fim_on_beta_endpoint() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/beta/completions"))
+        .and(body_partial_json(serde_json::json!({
+            "model": "deepseek-chat",
+            "prompt": "def fib(a):",
+            "stream": false
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_string(COMPLETION))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let completion = client(&server, Config::deepseek())
+        .complete_fim(fim())
+        .await
+        .unwrap();
+
+    let choice = &completion.choices[0];
+    assert_eq!(choice.text, "\n    if a < 2:\n        return a\n");
+    assert_eq!(choice.logprobs.as_ref().unwrap().text_offset, [11]);
+    assert_eq!(completion.attempts, 1);
+}
+
+#[tokio::test]
+async fn streams_fim_on_beta_endpoint() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/beta/completions"))
+        .and(body_partial_json(serde_json::json!({ "stream": true })))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(EVENTS, "text/event-stream"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let text: String = client(&server, Config::deepseek())
+        .stream_fim(fim())
+        .await
+        .unwrap()
+        .map(|chunk| chunk.unwrap().choices[0].text.clone())
+        .collect()
+        .await;
+
+    assert_eq!(text, "\n    if a < 2:\n        return a\n");
+}
+
+#[tokio::test]
+async fn fim_requires_beta_endpoint() {
+    let server = MockServer::start().await;
+
+    let error = client(&server, Config::openrouter())
+        .complete_fim(fim())
+        .await
+        .unwrap_err();
+
+    assert!(matches!(error, Error::Unsupported(_)));
+}
@@ -0,0 +1,86 @@
+use rgi::deepseek::completion::{Chunk, ChunkAccumulator, Object};
+use rgi::deepseek::request::{Chat, LogProbs, TopLogProbs};
+use rgi::deepseek::Model;
+use serde_json::json;
+
+#[test]
+fn serializes_logprobs_flags() {
+    let chat = |logprobs| Chat {
+        model: Some(Model::Chat),
+        logprobs,
+        ..Chat::default()
+    };
+
+    assert_eq!(
+        serde_json::to_value(chat(None)).unwrap(),
+        json!({"messages": [], "model": "deepseek-chat", "stream": false})
+    );
+
+    assert_eq!(
+        serde_json::to_value(chat(Some(LogProbs::with_top(TopLogProbs::new(3).unwrap())))).unwrap(),
+        json!({
+            "messages": [],
+            "model": "deepseek-chat",
+            "stream": false,
+            "logprobs": true,
+            "top_logprobs": 3
+        })
+    );
+}
+
+#[test]
+fn deserializes_logprobs() {
+    let response = r#"{
+        "id": "c1",
+        "object": "chat.completion",
+        "created": 1738000000,
+        "model": "deepseek-chat",
+        "system_fingerprint": "fp_3a5770e1b4",
+        "choices": [{
+            "index": 0,
+            "message": {"role": "assistant", "content": "Hi!"},
+            "logprobs": {"content": [
+                {"token": "Hi", "logprob": -0.01, "bytes": [72, 105], "top_logprobs": [
+                    {"token": "Hi", "logprob": -0.01, "bytes": [72, 105]},
+                    {"token": "Hello", "logprob": -4.6, "bytes": [72, 101, 108, 108, 111]}
+                ]},
+                {"token": "!", "logprob": -0.2, "bytes": [33], "top_logprobs": []}
+            ]},
+            "finish_reason": "stop"
+        }],
+        "usage": {"prompt_tokens": 5, "completion_tokens": 2, "total_tokens": 7}
+    }"#;
+
+    let object: Object = serde_json::from_str(response).unwrap();
+    let content = object.choices[0].logprobs.clone().unwrap().content.unwrap();
+
+    assert_eq!(content.len(), 2);
+    assert_eq!(content[0].bytes.as_deref(), Some(&b"Hi"[..]));
+    assert_eq!(content[0].top_logprobs[1].token, "Hello");
+    assert_eq!(content[1].logprob, -0.2);
+}
+
+#[test]
+fn accumulates_streamed_logprobs() {
+    let chunks = [
+        r#"{"id":"c1","object":"chat.completion.chunk","created":1738000000,"model":"deepseek-chat","choices":[{"index":0,"delta":{"role":"assistant","content":"Hi"},"logprobs":{"content":[{"token":"Hi","logprob":-0.01,"bytes":[72,105],"top_logprobs":[]}]},"finish_reason":null}]}"#,
+        r#"{"id":"c1","object":"chat.completion.chunk","created":1738000000,"model":"deepseek-chat","choices":[{"index":0,"delta":{"content":"!"},"logprobs":{"content":[{"token":"!","logprob":-0.2,"bytes":[33],"top_logprobs":[]}]},"finish_reason":"stop"}]}"#,
+    ];
+
+    let mut accumulator = ChunkAccumulator::new();
+    for chunk in chunks {
+        accumulator.push(serde_json::from_str::<Chunk>(chunk).unwrap());
+    }
+
+    let object = accumulator.finish().unwrap();
+    let tokens: Vec<_> = object.choices[0]
+        .logprobs
+        .as_ref()
+        .and_then(|logprobs| logprobs.content.as_ref())
+        .unwrap()
+        .iter()
+        .map(|token| token.token.as_str())
+        .collect();
+
+    assert_eq!(tokens, ["Hi", "!"]);
+}
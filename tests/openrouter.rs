@@ -0,0 +1,144 @@
+use rgi::deepseek::openrouter::{Effort, OpenRouter, Provider, Reasoning, Route, UsageAccounting};
+use rgi::deepseek::request::{Chat, Message};
+use rgi::deepseek::{Config, Model};
+use serde_json::{json, Value};
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+mod common;
+
+use common::{chunk, client, completion, events};
+
+/// Marks `body` as served by `provider` through OpenRouter, which only passes
+/// on a `system_fingerprint` if the provider reports one.
+fn served_by(mut body: Value, provider: &str) -> Value {
+    body["provider"] = Value::from(provider);
+    body["model"] = Value::from("deepseek/deepseek-r1");
+    body.as_object_mut().unwrap().remove("system_fingerprint");
+    body
+}
+
+fn chat() -> Chat {
+    Chat {
+        messages: vec![Message::User {
+            content: String::from("Hi"),
+            name: None,
+        }],
+        model: Some(Model::OpenRouterR1),
+        openrouter: Some(OpenRouter {
+            provider: Some(Provider {
+                order: Some(vec![String::from("DeepSeek")]),
+                allow_fallbacks: Some(false),
+                ..Provider::default()
+            }),
+            models: Some(vec![Model::OpenRouterChat]),
+            route: Some(Route::Fallback),
+            reasoning: Some(Reasoning {
+                effort: Some(Effort::Low),
+                exclude: Some(true),
+                ..Reasoning::default()
+            }),
+            usage: Some(UsageAccounting { include: true }),
+            ..OpenRouter::default()
+        }),
+        ..Chat::default()
+    }
+}
+
+#[tokio::test]
+async fn sends_extensions_to_openrouter() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/api/v1/chat/completions"))
+        .and(body_partial_json(json!({
+            "model": "deepseek/deepseek-r1",
+            "provider": {"order": ["DeepSeek"], "allow_fallbacks": false},
+            "models": ["deepseek/deepseek-chat"],
+            "route": "fallback",
+            "reasoning": {"effort": "low", "exclude": true},
+            "usage": {"include": true}
+        })))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(served_by(completion("Hello"), "DeepSeek")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let completion = client(&server, Config::openrouter())
+        .complete(chat())
+        .await
+        .unwrap();
+
+    assert_eq!(completion.provider.as_deref(), Some("DeepSeek"));
+    assert_eq!(completion.system_fingerprint, None);
+}
+
+#[tokio::test]
+async fn drops_extensions_for_deepseek() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/chat/completions"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(served_by(completion("Hello"), "DeepSeek")),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client(&server, Config::deepseek())
+        .complete(chat())
+        .await
+        .unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    let body: Value = serde_json::from_slice(&requests[0].body).unwrap();
+
+    for field in [
+        "provider",
+        "models",
+        "route",
+        "transforms",
+        "reasoning",
+        "usage",
+    ] {
+        assert!(body.get(field).is_none(), "{} was sent", field);
+    }
+}
+
+#[tokio::test]
+async fn collects_provider_from_stream() {
+    let server = MockServer::start().await;
+
+    let events = events([
+        served_by(
+            chunk(json!({"role": "assistant", "content": "Hel"}), None),
+            "Together",
+        ),
+        served_by(chunk(json!({"content": "lo"}), Some("stop")), "Together"),
+    ]);
+
+    Mock::given(method("POST"))
+        .and(path("/api/v1/chat/completions"))
+        .and(body_partial_json(json!({ "stream": true })))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(events, "text/event-stream"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let completion = client(&server, Config::openrouter())
+        .stream(chat())
+        .await
+        .unwrap()
+        .collect_completion()
+        .await
+        .unwrap();
+
+    assert_eq!(completion.provider.as_deref(), Some("Together"));
+    assert_eq!(
+        completion.choices[0].message.content.as_deref(),
+        Some("Hello")
+    );
+}
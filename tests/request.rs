@@ -0,0 +1,52 @@
+use rgi::deepseek::request::{Chat, Message, ResponseFormat, Stop, TopP};
+use rgi::deepseek::Model;
+
+#[test]
+fn builder_matches_struct() {
+    let messages = vec![
+        Message::System {
+            content: String::from("Answer in JSON."),
+            name: None,
+        },
+        Message::User {
+            content: String::from("Name three colors."),
+            name: None,
+        },
+    ];
+
+    let built = Chat::builder()
+        .messages(messages[0].clone(), messages[1..].to_vec())
+        .model(Model::Chat)
+        .top_p(TopP::new(0.9).unwrap())
+        .response_format(ResponseFormat::JsonObject)
+        .build();
+
+    let literal = Chat {
+        messages,
+        model: Some(Model::Chat),
+        top_p: Some(TopP::new(0.9).unwrap()),
+        response_format: Some(ResponseFormat::JsonObject),
+        ..Chat::default()
+    };
+
+    assert_eq!(
+        serde_json::to_value(built).unwrap(),
+        serde_json::to_value(literal).unwrap()
+    );
+}
+
+#[test]
+fn serializes_stop_sequences() {
+    let chat = Chat::builder()
+        .message(Message::User {
+            content: String::from("Count to ten."),
+            name: None,
+        })
+        .model(Model::Chat)
+        .stop(Stop::new(vec![String::from("5")]).unwrap())
+        .build();
+
+    let value = serde_json::to_value(chat).unwrap();
+    assert_eq!(value["stop"], serde_json::json!(["5"]));
+    assert!(value.get("stream_options").is_none());
+}
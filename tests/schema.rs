@@ -0,0 +1,154 @@
+use rgi::deepseek::schema::{Schema, SchemaType, SchemaTypes};
+use serde_json::json;
+
+#[test]
+fn keeps_unknown_keywords() {
+    let value = json!({
+        "type": ["string", "null"],
+        "format": "date",
+        "minLength": 10
+    });
+
+    let schema: Schema = serde_json::from_value(value.clone()).unwrap();
+
+    assert_eq!(
+        schema.type_,
+        Some(SchemaTypes::Multiple(vec![
+            SchemaType::String,
+            SchemaType::Null
+        ]))
+    );
+    assert_eq!(schema.extra["format"], "date");
+    assert_eq!(serde_json::to_value(&schema).unwrap(), value);
+}
+
+#[test]
+fn builds_nested_schemas() {
+    let schema = Schema::object()
+        .property(
+            "stops",
+            Schema::array(
+                Schema::object()
+                    .property("city", Schema::string())
+                    .optional_property("nights", Schema::integer().nullable()),
+            ),
+        )
+        .optional_property("budget", Schema::number());
+
+    assert_eq!(
+        serde_json::to_value(&schema).unwrap(),
+        json!({
+            "type": "object",
+            "properties": {
+                "stops": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "city": {"type": "string"},
+                            "nights": {"anyOf": [{"type": "integer"}, {"type": "null"}]}
+                        },
+                        "required": ["city"]
+                    }
+                },
+                "budget": {"type": "number"}
+            },
+            "required": ["stops"]
+        })
+    );
+}
+
+#[cfg(feature = "schemars")]
+mod derived {
+    use super::*;
+    use rgi::deepseek::schema::Items;
+    use rgi::deepseek::tools::ToolArgs;
+
+    /// Plan a trip
+    #[derive(serde::Deserialize, schemars::JsonSchema)]
+    #[allow(dead_code)]
+    struct Trip {
+        /// Cities to visit, in order
+        stops: Vec<Stop>,
+        /// Budget in USD
+        budget: Option<f64>,
+        pace: Pace,
+        /// Arrival date and city
+        arrival: (u32, String),
+    }
+
+    impl ToolArgs for Trip {
+        fn parameters() -> Schema {
+            Schema::for_type::<Self>()
+        }
+    }
+
+    #[derive(serde::Deserialize, schemars::JsonSchema)]
+    #[allow(dead_code)]
+    struct Stop {
+        city: String,
+        nights: u8,
+    }
+
+    #[derive(serde::Deserialize, schemars::JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    #[allow(dead_code)]
+    enum Pace {
+        Relaxed,
+        Packed,
+    }
+
+    #[test]
+    fn derives_tool_parameters() {
+        let schema = Trip::parameters();
+        let value = serde_json::to_value(&schema).unwrap();
+
+        assert_eq!(value["description"], "Plan a trip");
+        assert_eq!(value["required"], json!(["stops", "pace", "arrival"]));
+        assert!(value.get("title").is_none());
+        assert!(value.get("$schema").is_none());
+
+        let properties = schema.properties.as_ref().unwrap();
+
+        let stops = &properties["stops"];
+        assert_eq!(
+            stops.description.as_deref(),
+            Some("Cities to visit, in order")
+        );
+        let Some(Items::Single(stop)) = &stops.items else {
+            panic!("expected a single items schema, got {:?}", stops.items)
+        };
+        assert_eq!(stop.type_, Some(SchemaTypes::Single(SchemaType::Object)));
+        assert_eq!(
+            stop.required,
+            Some(vec![String::from("city"), String::from("nights")])
+        );
+
+        assert_eq!(
+            properties["budget"].type_,
+            Some(SchemaTypes::Multiple(vec![
+                SchemaType::Number,
+                SchemaType::Null
+            ]))
+        );
+        assert_eq!(
+            properties["pace"].enum_,
+            Some(vec![json!("relaxed"), json!("packed")])
+        );
+
+        let Some(Items::Tuple(arrival)) = &properties["arrival"].items else {
+            panic!(
+                "expected tuple items, got {:?}",
+                properties["arrival"].items
+            )
+        };
+        assert_eq!(
+            arrival[0].type_,
+            Some(SchemaTypes::Single(SchemaType::Integer))
+        );
+        assert_eq!(
+            arrival[1].type_,
+            Some(SchemaTypes::Single(SchemaType::String))
+        );
+    }
+}
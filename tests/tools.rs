@@ -0,0 +1,243 @@
+use rgi::deepseek::completion::{
+    Chunk, ChunkAccumulator, FinishReason, FunctionCall, Object, ToolCall, ToolType,
+};
+use rgi::deepseek::request::{Chat, Message, Tool, ToolChoice};
+use rgi::deepseek::schema::Schema;
+use rgi::deepseek::tools::{ToolArgs, ToolRegistry};
+use rgi::deepseek::Model;
+use rgi::ToolError;
+use serde_json::json;
+
+fn weather_tool() -> Tool {
+    Tool::Function {
+        description: String::from(
+            "Get weather of a location, the user should supply a location first",
+        ),
+        name: String::from("get_weather"),
+        parameters: Schema::object().property(
+            "location",
+            Schema::string().description("The city and state, e.g. San Francisco, CA"),
+        ),
+    }
+}
+
+#[test]
+fn serializes_tools() {
+    let chat = Chat {
+        messages: vec![Message::User {
+            content: String::from("How's the weather in Hangzhou?"),
+            name: None,
+        }],
+        model: Some(Model::Chat),
+        tools: Some(vec![weather_tool()]),
+        tool_choice: Some(ToolChoice::Function(String::from("get_weather"))),
+        ..Chat::default()
+    };
+
+    assert_eq!(
+        serde_json::to_value(&chat).unwrap(),
+        json!({
+            "messages": [{"role": "user", "content": "How's the weather in Hangzhou?"}],
+            "model": "deepseek-chat",
+            "stream": false,
+            "tools": [{
+                "type": "function",
+                "function": {
+                    "name": "get_weather",
+                    "description": "Get weather of a location, the user should supply a location first",
+                    "parameters": {
+                        "type": "object",
+                        "properties": {
+                            "location": {
+                                "type": "string",
+                                "description": "The city and state, e.g. San Francisco, CA"
+                            }
+                        },
+                        "required": ["location"]
+                    }
+                }
+            }],
+            "tool_choice": {"type": "function", "function": {"name": "get_weather"}}
+        })
+    );
+
+    for (choice, expected) in [
+        (ToolChoice::None, "none"),
+        (ToolChoice::Auto, "auto"),
+        (ToolChoice::Required, "required"),
+    ] {
+        assert_eq!(serde_json::to_value(choice).unwrap(), json!(expected));
+    }
+}
+
+#[test]
+fn round_trips_tool_calls() {
+    let response = r#"{
+        "id": "8a3e5cb1-5a4e-4b9f-9e5c-2d7c3b1f0a11",
+        "object": "chat.completion",
+        "created": 1738000000,
+        "model": "deepseek-chat",
+        "choices": [{
+            "index": 0,
+            "message": {
+                "role": "assistant",
+                "content": "",
+                "tool_calls": [{
+                    "index": 0,
+                    "id": "call_0_3bd1e4b6-4c0e-4f4a-9d43-41c1a2e8c2b5",
+                    "type": "function",
+                    "function": {"name": "get_weather", "arguments": "{\"location\":\"Hangzhou\"}"}
+                }]
+            },
+            "logprobs": null,
+            "finish_reason": "tool_calls"
+        }],
+        "usage": {
+            "prompt_tokens": 236,
+            "completion_tokens": 22,
+            "total_tokens": 258,
+            "prompt_tokens_details": {"cached_tokens": 0},
+            "prompt_cache_hit_tokens": 0,
+            "prompt_cache_miss_tokens": 236
+        },
+        "system_fingerprint": "fp_3a5770e1b4"
+    }"#;
+
+    let object: Object = serde_json::from_str(response).unwrap();
+    let choice = &object.choices[0];
+    assert!(matches!(choice.finish_reason, FinishReason::ToolCalls));
+
+    let tool_calls = choice.message.tool_calls.clone().unwrap();
+    assert_eq!(tool_calls[0].function.name, "get_weather");
+
+    let messages = vec![
+        Message::Assistant {
+            content: None,
+            name: None,
+            tool_calls: Some(tool_calls),
+            prefix: None,
+            reasoning_content: None,
+        },
+        Message::Tool {
+            content: String::from("24℃"),
+            tool_call_id: String::from("call_0_3bd1e4b6-4c0e-4f4a-9d43-41c1a2e8c2b5"),
+        },
+    ];
+
+    assert_eq!(
+        serde_json::to_value(&messages).unwrap(),
+        json!([
+            {
+                "role": "assistant",
+                "content": null,
+                "tool_calls": [{
+                    "id": "call_0_3bd1e4b6-4c0e-4f4a-9d43-41c1a2e8c2b5",
+                    "type": "function",
+                    "function": {"name": "get_weather", "arguments": "{\"location\":\"Hangzhou\"}"}
+                }]
+            },
+            {
+                "role": "tool",
+                "content": "24℃",
+                "tool_call_id": "call_0_3bd1e4b6-4c0e-4f4a-9d43-41c1a2e8c2b5"
+            }
+        ])
+    );
+}
+
+#[test]
+fn accumulates_streamed_tool_calls() {
+    let chunks = [
+        r#"{"id":"c1","object":"chat.completion.chunk","created":1738000000,"model":"deepseek-chat","system_fingerprint":"fp_3a5770e1b4","choices":[{"index":0,"delta":{"role":"assistant","content":null,"tool_calls":[{"index":0,"id":"call_0_1","type":"function","function":{"name":"get_weather","arguments":""}}]},"logprobs":null,"finish_reason":null}]}"#,
+        r#"{"id":"c1","object":"chat.completion.chunk","created":1738000000,"model":"deepseek-chat","system_fingerprint":"fp_3a5770e1b4","choices":[{"index":0,"delta":{"tool_calls":[{"index":0,"function":{"arguments":"{\"location\":"}}]},"logprobs":null,"finish_reason":null}]}"#,
+        r#"{"id":"c1","object":"chat.completion.chunk","created":1738000000,"model":"deepseek-chat","system_fingerprint":"fp_3a5770e1b4","choices":[{"index":0,"delta":{"tool_calls":[{"index":0,"function":{"arguments":"\"Hangzhou\"}"}}]},"logprobs":null,"finish_reason":null}]}"#,
+        r#"{"id":"c1","object":"chat.completion.chunk","created":1738000000,"model":"deepseek-chat","system_fingerprint":"fp_3a5770e1b4","choices":[{"index":0,"delta":{"content":""},"logprobs":null,"finish_reason":"tool_calls"}],"usage":{"prompt_tokens":236,"completion_tokens":22,"total_tokens":258}}"#,
+    ];
+
+    let mut accumulator = ChunkAccumulator::new();
+    for chunk in chunks {
+        accumulator.push(serde_json::from_str::<Chunk>(chunk).unwrap());
+    }
+
+    let object = accumulator.finish().unwrap();
+    let tool_calls = object.choices[0].message.tool_calls.as_ref().unwrap();
+
+    assert_eq!(tool_calls.len(), 1);
+    assert_eq!(tool_calls[0].id, "call_0_1");
+    assert_eq!(tool_calls[0].function.name, "get_weather");
+    assert_eq!(
+        tool_calls[0].function.arguments,
+        r#"{"location":"Hangzhou"}"#
+    );
+}
+
+#[derive(serde::Deserialize)]
+struct Location {
+    location: String,
+}
+
+impl ToolArgs for Location {
+    fn parameters() -> Schema {
+        let Tool::Function { parameters, .. } = weather_tool();
+        parameters
+    }
+}
+
+fn tool_call(name: &str, arguments: &str) -> ToolCall {
+    ToolCall {
+        id: String::from("call_0_1"),
+        type_: ToolType::Function,
+        function: FunctionCall {
+            name: String::from(name),
+            arguments: String::from(arguments),
+        },
+    }
+}
+
+#[tokio::test]
+async fn registry_dispatches_tool_calls() {
+    let mut registry = ToolRegistry::new();
+    registry.register(
+        "get_weather",
+        "Get weather of a location",
+        |args: Location| async move {
+            match args.location.as_str() {
+                "Hangzhou" => Ok(json!({ "celsius": 24 })),
+                location => Err(format!("unknown location {}", location)),
+            }
+        },
+    );
+
+    assert_eq!(registry.definitions().len(), 1);
+
+    let output = registry
+        .call(&tool_call("get_weather", r#"{"location":"Hangzhou"}"#))
+        .await
+        .unwrap();
+    assert_eq!(output, r#"{"celsius":24}"#);
+
+    assert!(matches!(
+        registry.call(&tool_call("get_time", "{}")).await,
+        Err(ToolError::UnknownTool(name)) if name == "get_time"
+    ));
+    assert!(matches!(
+        registry.call(&tool_call("get_weather", "{}")).await,
+        Err(ToolError::InvalidArguments { .. })
+    ));
+
+    let replies = registry
+        .execute_all(&[tool_call("get_weather", r#"{"location":"Atlantis"}"#)])
+        .await;
+    let Message::Tool {
+        content,
+        tool_call_id,
+    } = &replies[0]
+    else {
+        panic!("expected a tool reply");
+    };
+    assert_eq!(tool_call_id, "call_0_1");
+    assert_eq!(
+        content,
+        "Error: tool get_weather failed: unknown location Atlantis"
+    );
+}